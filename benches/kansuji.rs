@@ -0,0 +1,30 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use kansuji::Kansuji;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+fn bench_convert(c: &mut Criterion) {
+    let n: u128 = 1234_5678_9012_3456_7890;
+    let kansuji = Kansuji::from(n);
+    c.bench_function("from_u128", |b| b.iter(|| Kansuji::from(black_box(n))));
+    c.bench_function("to_u128", |b| b.iter(|| u128::from(black_box(kansuji))));
+    c.bench_function("to_f64", |b| b.iter(|| f64::from(black_box(kansuji))));
+    c.bench_function("eq", |b| {
+        let other = Kansuji::from(n + 1);
+        b.iter(|| black_box(kansuji) == black_box(other))
+    });
+    c.bench_function("hash_set_insert_1000", |b| {
+        let v: Vec<Kansuji> = (0..1000_u32).map(|n| Kansuji::from(n * 7919)).collect();
+        b.iter(|| v.iter().copied().collect::<HashSet<Kansuji>>())
+    });
+}
+
+fn bench_text(c: &mut Criterion) {
+    let s = "千二百三十四京五千六百七十八兆九千十二億三千四百五十六万七千八百九十";
+    let kansuji = Kansuji::try_from(s).unwrap();
+    c.bench_function("parse", |b| b.iter(|| Kansuji::try_from(black_box(s))));
+    c.bench_function("to_string", |b| b.iter(|| black_box(kansuji).to_string()));
+}
+
+criterion_group!(benches, bench_convert, bench_text);
+criterion_main!(benches);
@@ -0,0 +1,174 @@
+#ifndef KANSUJI_H
+#define KANSUJI_H
+
+/* このファイルはcbindgenで生成したものである。直接編集しないこと。 */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * 結果を書き込むバッファに必要な大きさ（終端の`NUL`を含む）
+ *
+ * この大きさのバッファを渡せば、`KANSUJI_STATUS_BUFFER_TOO_SMALL`になることはない。
+ */
+#define KANSUJI_BUFFER_CAPACITY 160
+
+/**
+ * 関数の結果
+ */
+typedef enum KansujiStatus {
+  /**
+   * 成功した
+   */
+  KANSUJI_STATUS_OK = 0,
+  /**
+   * 漢数字として読めない（UTF-8として正しくない場合を含む）
+   */
+  KANSUJI_STATUS_PARSE_ERROR = 1,
+  /**
+   * 予期しない文字がある
+   */
+  KANSUJI_STATUS_UNEXPECTED_CHAR = 2,
+  /**
+   * 予期しない位置で終わっている
+   */
+  KANSUJI_STATUS_UNEXPECTED_END = 3,
+  /**
+   * 扱える範囲を超えている
+   */
+  KANSUJI_STATUS_TOO_LARGE = 4,
+  /**
+   * 値が範囲外である
+   */
+  KANSUJI_STATUS_OUT_OF_RANGE = 5,
+  /**
+   * 必要なポインタが`NULL`である
+   */
+  KANSUJI_STATUS_NULL_POINTER = 6,
+  /**
+   * 結果を書き込むバッファが小さい
+   */
+  KANSUJI_STATUS_BUFFER_TOO_SMALL = 7,
+} KansujiStatus;
+
+/**
+ * 漢数字の値
+ *
+ * Cには128ビットの整数型がないため、整数部を上位と下位の64ビットに分けて持つ。
+ */
+typedef struct KansujiValue {
+  /**
+   * 整数部の上位64ビット
+   */
+  uint64_t integer_high;
+  /**
+   * 整数部の下位64ビット
+   */
+  uint64_t integer_low;
+  /**
+   * 小数部（毛を単位とした0〜999）
+   */
+  uint16_t fraction;
+} KansujiValue;
+
+/**
+ * 漢数字への変換時の書式
+ */
+typedef struct KansujiFormatOptions {
+  /**
+   * 大字（壱・弐・参・拾・萬など）を使う
+   */
+  bool daiji;
+  /**
+   * 千の前の一を省略しない（一千）
+   */
+  bool explicit_one;
+} KansujiFormatOptions;
+
+
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * 漢数字を読み、その値を`out`に書き込む
+ *
+ * # Safety
+ *
+ * `s`は`len`バイト読めるUTF-8の文字列でなければならない（`len`が0の場合は`NULL`でもよい）。
+ * `out`は書き込めるものでなければならない。
+ */
+enum KansujiStatus kansuji_parse(const char *s,
+                                 size_t len,
+                                 struct KansujiValue *out);
+
+/**
+ * 漢数字を読み、`12345.6`のような十進の文字列として`buf`に書き込む
+ *
+ * `written`には終端の`NUL`を除いた長さを書き込む。`written`は`NULL`でもよい。
+ *
+ * # Safety
+ *
+ * `s`は`len`バイト読めるUTF-8の文字列でなければならない（`len`が0の場合は`NULL`でもよい）。
+ * `buf`は`buf_len`バイト書き込めるものでなければならない。
+ */
+enum KansujiStatus kansuji_parse_to_decimal(const char *s,
+                                            size_t len,
+                                            char *buf,
+                                            size_t buf_len,
+                                            size_t *written);
+
+/**
+ * 値を漢数字にして`buf`に書き込む
+ *
+ * `options`が`NULL`の場合は既定の書式とする。
+ * `written`には終端の`NUL`を除いた長さを書き込む。`written`は`NULL`でもよい。
+ *
+ * # Safety
+ *
+ * `options`は`NULL`であるか、読めるものでなければならない。
+ * `buf`は`buf_len`バイト書き込めるものでなければならない。
+ */
+enum KansujiStatus kansuji_format(struct KansujiValue value,
+                                  const struct KansujiFormatOptions *options,
+                                  char *buf,
+                                  size_t buf_len,
+                                  size_t *written);
+
+/**
+ * `12345.6`のような十進の文字列を漢数字にして`buf`に書き込む
+ *
+ * 小数点以下は三桁までとする。
+ * `options`が`NULL`の場合は既定の書式とする。
+ * `written`には終端の`NUL`を除いた長さを書き込む。`written`は`NULL`でもよい。
+ *
+ * # Safety
+ *
+ * `s`は`len`バイト読めるUTF-8の文字列でなければならない（`len`が0の場合は`NULL`でもよい）。
+ * `options`は`NULL`であるか、読めるものでなければならない。
+ * `buf`は`buf_len`バイト書き込めるものでなければならない。
+ */
+enum KansujiStatus kansuji_format_decimal(const char *s,
+                                          size_t len,
+                                          const struct KansujiFormatOptions *options,
+                                          char *buf,
+                                          size_t buf_len,
+                                          size_t *written);
+
+/**
+ * このスレッドで最後に失敗した関数のエラーメッセージを返す
+ *
+ * 返す文字列は終端の`NUL`付きのUTF-8であり、このスレッドで次に関数が失敗するまで有効である。
+ * まだ失敗していない場合は`NULL`を返す。
+ */
+const char *kansuji_last_error_message(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* KANSUJI_H */
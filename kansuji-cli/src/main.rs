@@ -0,0 +1,406 @@
+//! 漢数字と数字を相互に変換するコマンドラインツール
+//!
+//! ```text
+//! kansuji to-kanji [--daiji] [--explicit-one] [NUMBER...]
+//! kansuji to-num [KANJI...]
+//! kansuji rewrite --to arabic
+//! kansuji rewrite --to kanji [--daiji] [--explicit-one]
+//! kansuji read [KANJI...]
+//! ```
+//!
+//! 引数を省略した場合は標準入力を一行ずつ変換する。
+//! 終了コードは、成功で0、変換できないものがあった場合は1、引数が正しくない場合は2、入出力のエラーは3とする。
+
+use kansuji::{Kansuji, KansujiError, KansujiFormat};
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+const USAGE: &str = "\
+usage: kansuji to-kanji [--daiji] [--explicit-one] [NUMBER...]
+       kansuji to-num [KANJI...]
+       kansuji rewrite --to arabic
+       kansuji rewrite --to kanji [--daiji] [--explicit-one]
+       kansuji read [KANJI...]
+
+With no NUMBER or KANJI, each line of standard input is converted.";
+
+/// 書き換えの向き
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Target {
+    /// 漢数字を算用数字にする
+    Arabic,
+    /// 算用数字を漢数字にする
+    Kanji,
+}
+
+/// サブコマンド
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Command {
+    ToKanji(KansujiFormat, Vec<String>),
+    ToNum(Vec<String>),
+    Rewrite(Target, KansujiFormat),
+    Read(Vec<String>),
+    Help,
+}
+
+/// 実行時のエラー
+#[derive(Debug)]
+enum Error {
+    /// 引数が正しくない
+    Usage(String),
+    /// 変換できないものがあった（メッセージは出力済み）
+    Convert,
+    /// 入出力のエラー
+    Io(io::Error),
+}
+
+impl Error {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Error::Convert => 1,
+            Error::Usage(_) => 2,
+            Error::Io(_) => 3,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(message) => write!(f, "{}\n\n{}", message, USAGE),
+            Error::Convert => Ok(()),
+            Error::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+fn main() {
+    let result = parse_args(std::env::args().skip(1)).and_then(run);
+    match result {
+        Ok(()) => {}
+        // 出力先が閉じられた場合は、それ以上書く必要がないため成功とする
+        Err(Error::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(Error::Convert) => process::exit(1),
+        Err(e) => {
+            eprintln!("kansuji: {}", e);
+            process::exit(e.exit_code());
+        }
+    }
+}
+
+/// 引数を読む
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Command, Error> {
+    let subcommand = match args.next() {
+        Some(subcommand) => subcommand,
+        None => return Err(Error::Usage("missing subcommand".to_string())),
+    };
+    let mut format = KansujiFormat::default();
+    let mut target = None;
+    let mut values = Vec::new();
+    let mut only_values = false;
+    while let Some(arg) = args.next() {
+        if only_values || !arg.starts_with("--") {
+            values.push(arg);
+            continue;
+        }
+        match arg.as_str() {
+            "--" => only_values = true,
+            "--daiji" => format.daiji = true,
+            "--explicit-one" => format.explicit_one = true,
+            "--help" => return Ok(Command::Help),
+            "--to" => {
+                target = match args.next().as_deref() {
+                    Some("arabic") => Some(Target::Arabic),
+                    Some("kanji") => Some(Target::Kanji),
+                    Some(s) => return Err(Error::Usage(format!("unknown target: {}", s))),
+                    None => return Err(Error::Usage("missing value for --to".to_string())),
+                }
+            }
+            _ => return Err(Error::Usage(format!("unknown option: {}", arg))),
+        }
+    }
+    let has_format = format != KansujiFormat::default();
+    let command = match subcommand.as_str() {
+        "to-kanji" => Command::ToKanji(format, values),
+        "to-num" if !has_format => Command::ToNum(values),
+        "read" if !has_format => Command::Read(values),
+        "rewrite" => {
+            if !values.is_empty() {
+                return Err(Error::Usage(
+                    "rewrite reads standard input only".to_string(),
+                ));
+            }
+            match target {
+                Some(Target::Arabic) if has_format => {
+                    return Err(Error::Usage(
+                        "--to arabic does not take format options".to_string(),
+                    ))
+                }
+                Some(target) => Command::Rewrite(target, format),
+                None => return Err(Error::Usage("missing --to".to_string())),
+            }
+        }
+        "to-num" | "read" => {
+            return Err(Error::Usage(format!(
+                "{} does not take format options",
+                subcommand
+            )))
+        }
+        "help" | "-h" | "--help" => Command::Help,
+        _ => return Err(Error::Usage(format!("unknown subcommand: {}", subcommand))),
+    };
+    if target.is_some() && !matches!(command, Command::Rewrite(..)) {
+        return Err(Error::Usage("--to is only for rewrite".to_string()));
+    }
+    Ok(command)
+}
+
+#[test]
+fn check_parse_args_1() {
+    fn parse(args: &[&str]) -> Result<Command, Error> {
+        parse_args(args.iter().map(|s| s.to_string()))
+    }
+
+    let daiji = KansujiFormat {
+        daiji: true,
+        ..KansujiFormat::default()
+    };
+    assert_eq!(
+        parse(&["to-kanji", "--daiji", "12"]).unwrap(),
+        Command::ToKanji(daiji, vec!["12".to_string()])
+    );
+    assert_eq!(
+        parse(&["rewrite", "--to", "arabic"]).unwrap(),
+        Command::Rewrite(Target::Arabic, KansujiFormat::default())
+    );
+    assert_eq!(
+        parse(&["to-num", "--", "--三"]).unwrap(),
+        Command::ToNum(vec!["--三".to_string()])
+    );
+    for args in [
+        &[][..],
+        &["rewrite"],
+        &["rewrite", "--to", "roman"],
+        &["rewrite", "--to", "arabic", "--daiji"],
+        &["rewrite", "--explicit-one", "--to", "arabic"],
+        &["to-num", "--daiji"],
+        &["read", "--to", "kanji"],
+        &["to-kanji", "--bold"],
+    ] {
+        assert_eq!(parse(args).unwrap_err().exit_code(), 2);
+    }
+}
+
+fn run(command: Command) -> Result<(), Error> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    match command {
+        Command::ToKanji(format, values) => convert_each(&values, &mut out, |s| {
+            Kansuji::parse_decimal(s).map(|kansuji| kansuji.to_string_with(&format))
+        }),
+        Command::ToNum(values) => convert_each(&values, &mut out, |s| {
+            Kansuji::try_from(s).map(|kansuji| kansuji.to_decimal_string())
+        }),
+        Command::Read(values) => convert_each(&values, &mut out, |s| {
+            Kansuji::try_from(s).map(|kansuji| kansuji.reading())
+        }),
+        Command::Rewrite(target, format) => {
+            let stdin = io::stdin();
+            let mut input = stdin.lock();
+            let mut line = String::new();
+            while input.read_line(&mut line)? > 0 {
+                let rewritten = match target {
+                    Target::Arabic => rewrite_to_arabic(&line),
+                    Target::Kanji => rewrite_to_kanji(&line, &format),
+                };
+                out.write_all(rewritten.as_bytes())?;
+                out.flush()?;
+                line.clear();
+            }
+            Ok(())
+        }
+        Command::Help => {
+            writeln!(out, "{}", USAGE)?;
+            Ok(())
+        }
+    }
+}
+
+/// 引数を一つずつ、引数がない場合は標準入力を一行ずつ変換する
+///
+/// 変換できないものはエラーを出力して読み飛ばし、最後に[`Error::Convert`]を返す。
+fn convert_each<W, F>(values: &[String], out: &mut W, convert: F) -> Result<(), Error>
+where
+    W: Write,
+    F: Fn(&str) -> Result<String, KansujiError>,
+{
+    let mut failed = false;
+    let mut convert_one = |s: &str, out: &mut W| -> io::Result<()> {
+        match convert(s) {
+            Ok(converted) => {
+                writeln!(out, "{}", converted)?;
+                out.flush()
+            }
+            Err(e) => {
+                eprintln!("kansuji: {}: {}", s, e);
+                failed = true;
+                Ok(())
+            }
+        }
+    };
+    if values.is_empty() {
+        let stdin = io::stdin();
+        for line in stdin.lock().lines() {
+            let line = line?;
+            let line = line.trim();
+            if !line.is_empty() {
+                convert_one(line, out)?;
+            }
+        }
+    } else {
+        for value in values {
+            convert_one(value, out)?;
+        }
+    }
+    if failed {
+        Err(Error::Convert)
+    } else {
+        Ok(())
+    }
+}
+
+/// 文字列中の漢数字を算用数字に書き換える
+///
+/// 漢数字として読めない部分と、算用数字だけの部分はそのまま残す。
+/// `千葉`・`統一`のような語を壊さないよう、一文字だけの漢数字は後ろに助数詞や単位が続く場合に限って書き換える。
+fn rewrite_to_arabic(s: &str) -> String {
+    kansuji::replace_all(s, |m| m.kansuji().to_decimal_string())
+}
+
+#[test]
+fn check_rewrite_to_arabic_1() {
+    assert_eq!(
+        rewrite_to_arabic("三百二十円と一万二千五百円\n"),
+        "320円と12500円\n"
+    );
+    assert_eq!(rewrite_to_arabic("１２万人"), "120000人");
+    assert_eq!(rewrite_to_arabic("一二分五厘"), "1.25");
+    assert_eq!(rewrite_to_arabic("2024年"), "2024年");
+    assert_eq!(rewrite_to_arabic("羊毛と三三万"), "羊毛と三三万");
+    assert_eq!(rewrite_to_arabic("千葉県"), "千葉県");
+    assert_eq!(rewrite_to_arabic("一般的に統一する"), "一般的に統一する");
+    assert_eq!(rewrite_to_arabic("七夕と一石二鳥"), "七夕と一石二鳥");
+    assert_eq!(
+        rewrite_to_arabic("万一の場合は三日月"),
+        "万一の場合は三日月"
+    );
+    assert_eq!(rewrite_to_arabic("一人で三個"), "1人で3個");
+}
+
+/// 文字列中の算用数字を漢数字に書き換える
+///
+/// `12,345`のように三桁ずつ区切るカンマは数の一部として読む。
+/// 小数点以下が四桁以上あるものや、大きすぎるものはそのまま残す。
+fn rewrite_to_kanji(s: &str, format: &KansujiFormat) -> String {
+    let mut rewritten = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(c) = rest.chars().next() {
+        let mut decimal = String::new();
+        let mut len = 0;
+        let mut chars = rest.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '0'..='9' => decimal.push(c),
+                '０'..='９' => decimal.push((b'0' + (c as u32 - '０' as u32) as u8) as char),
+                // 小数点は後ろに数字が続く場合だけ含める
+                '.' | '．'
+                    if !decimal.is_empty()
+                        && !decimal.contains('.')
+                        && chars.peek().map_or(false, |(_, c)| {
+                            c.is_ascii_digit() || ('０'..='９').contains(c)
+                        }) =>
+                {
+                    decimal.push('.')
+                }
+                // 位取りのカンマは、三桁ずつ区切られている場合だけ含める
+                ',' | '，' if is_digit_group(&rest[..i], &rest[i + c.len_utf8()..]) => (),
+                _ => break,
+            }
+            len = i + c.len_utf8();
+        }
+        if len == 0 {
+            rewritten.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        match Kansuji::parse_decimal(&decimal) {
+            Ok(kansuji) => {
+                // Stringへの書き込みは失敗しない
+                let _ = kansuji.write_to(&mut rewritten, format);
+            }
+            Err(_) => rewritten.push_str(&rest[..len]),
+        }
+        rest = &rest[len..];
+    }
+    rewritten
+}
+
+/// カンマの前まで`before`と後ろ`after`が、三桁ずつの区切りになっているかどうかを判定する
+fn is_digit_group(before: &str, after: &str) -> bool {
+    let is_digit = |c: &char| c.is_ascii_digit() || ('０'..='９').contains(c);
+    let group = before.rsplit([',', '，']).next().unwrap_or(before);
+    let width = group.chars().count();
+    let mut after = after.chars();
+    group.chars().all(|c| is_digit(&c))
+        && (1..=3).contains(&width)
+        && (width == 3 || !before.contains([',', '，']))
+        && (0..3).all(|_| after.next().map_or(false, |c| is_digit(&c)))
+        && !after.next().map_or(false, |c| is_digit(&c))
+}
+
+#[test]
+fn check_rewrite_to_kanji_1() {
+    let format = KansujiFormat::default();
+    assert_eq!(
+        rewrite_to_kanji("320円と12500円\n", &format),
+        "三百二十円と一万二千五百円\n"
+    );
+    assert_eq!(rewrite_to_kanji("１.５倍", &format), "一五分倍");
+    assert_eq!(rewrite_to_kanji("1.2345と3.", &format), "1.2345と三.");
+    let daiji = KansujiFormat {
+        daiji: true,
+        ..KansujiFormat::default()
+    };
+    assert_eq!(rewrite_to_kanji("金10000円", &daiji), "金壱萬円");
+}
+
+#[test]
+fn check_rewrite_to_kanji_2() {
+    let format = KansujiFormat::default();
+    assert_eq!(
+        rewrite_to_kanji("12,345円", &format),
+        "一万二千三百四十五円"
+    );
+    assert_eq!(
+        rewrite_to_kanji("1,234,567.5人", &format),
+        "百二十三万四千五百六十七五分人"
+    );
+    assert_eq!(
+        rewrite_to_kanji("１２，３４５", &format),
+        "一万二千三百四十五"
+    );
+    assert_eq!(rewrite_to_kanji("1,2,3", &format), "一,二,三");
+    assert_eq!(rewrite_to_kanji("1,2345", &format), "一,二千三百四十五");
+    assert_eq!(
+        rewrite_to_kanji("1234,567", &format),
+        "千二百三十四,五百六十七"
+    );
+}
@@ -0,0 +1,84 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn kansuji(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_kansuji"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn check_to_kanji_1() {
+    let output = kansuji(&["to-kanji", "12345", "1.5"], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stdout, "一万二千三百四十五\n一五分\n".as_bytes());
+    let output = kansuji(&["to-kanji", "--daiji"], "35000\n\n1000\n");
+    assert_eq!(output.stdout, "参萬伍阡\n壱阡\n".as_bytes());
+}
+
+#[test]
+fn check_to_num_1() {
+    let output = kansuji(&["to-num", "一万二千三百四十五"], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stdout, b"12345\n");
+    let output = kansuji(&["to-num"], "三百\n三百円\n一二分五厘\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(output.stdout, b"300\n1.25\n");
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "kansuji: 三百円: unexpected char: 円\n"
+    );
+}
+
+#[test]
+fn check_rewrite_1() {
+    let output = kansuji(
+        &["rewrite", "--to", "arabic"],
+        "定価三百二十円\n在庫は一万二千個\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "定価320円\n在庫は12000個\n"
+    );
+    let output = kansuji(&["rewrite", "--to", "arabic"], "千葉県で一つ\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "千葉県で1つ\n");
+    let output = kansuji(&["rewrite", "--to", "kanji"], "定価320円");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "定価三百二十円");
+    let output = kansuji(&["rewrite", "--to", "kanji"], "定価12,345円");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "定価一万二千三百四十五円"
+    );
+}
+
+#[test]
+fn check_read_1() {
+    let output = kansuji(&["read", "三百", "一兆"], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "さんびゃく\nいっちょう\n"
+    );
+}
+
+#[test]
+fn check_usage_1() {
+    let output = kansuji(&["rewrite"], "");
+    assert_eq!(output.status.code(), Some(2));
+    let output = kansuji(&["to-num", "--daiji"], "");
+    assert_eq!(output.status.code(), Some(2));
+    let output = kansuji(&["--help"], "");
+    assert_eq!(output.status.code(), Some(0));
+}
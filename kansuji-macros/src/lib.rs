@@ -0,0 +1,181 @@
+//! # 概要
+//!
+//! コンパイル時に漢数字を解析・変換するためのマクロを提供するcrateである。
+//!
+//! `kansuji!`は漢数字の文字列リテラルを読み、[`kansuji::Kansuji`]の定数式に展開する。
+//! 漢数字として正しくない場合は、[`kansuji::KansujiError`]のメッセージでコンパイルエラーとする。
+//!
+//! `kansuji_str!`は数値リテラルを漢数字の`&'static str`に展開する。
+//!
+//! ```
+//! use kansuji::{Kansuji, KansujiError, KANSUJI_LIMIT};
+//! use kansuji_macros::{kansuji, kansuji_str};
+//!
+//! const SANBYAKU_NIJUU: Kansuji = kansuji!("三百二十");
+//! assert_eq!(u128::from(SANBYAKU_NIJUU), 320);
+//!
+//! const LABEL: &str = kansuji_str!(320);
+//! assert_eq!(LABEL, "三百二十");
+//! ```
+//!
+//! ```compile_fail
+//! use kansuji_macros::kansuji;
+//!
+//! // error: unexpected char: 円
+//! let n = kansuji!("三百円");
+//! ```
+//!
+//! `kansuji_str!`は漢数字で表せない10の24乗以上の値をコンパイルエラーとする。
+//!
+//! ```compile_fail
+//! use kansuji_macros::kansuji_str;
+//!
+//! // error: too large
+//! let s = kansuji_str!(1_0000_0000_0000_0000_0000_0000);
+//! ```
+
+use kansuji::{Kansuji, KansujiError, KANSUJI_LIMIT};
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use std::convert::TryFrom;
+
+/// 漢数字の文字列リテラルを[`kansuji::Kansuji`]の定数式に展開する
+#[proc_macro]
+pub fn kansuji(input: TokenStream) -> TokenStream {
+    let (literal, span) = match single_literal(input) {
+        Ok(literal) => literal,
+        Err((message, span)) => return compile_error(&message, span),
+    };
+    let s = match string_value(&literal) {
+        Some(s) => s,
+        None => return compile_error("expected a string literal", span),
+    };
+    match Kansuji::try_from(s.as_str()) {
+        Ok(kansuji) => format!("::kansuji::Kansuji::from_mou({}_u128)", kansuji.to_mou())
+            .parse()
+            .unwrap_or_default(),
+        Err(e) => compile_error(&e.to_string(), span),
+    }
+}
+
+/// 数値リテラルを漢数字の`&'static str`に展開する
+#[proc_macro]
+pub fn kansuji_str(input: TokenStream) -> TokenStream {
+    let (literal, span) = match single_literal(input) {
+        Ok(literal) => literal,
+        Err((message, span)) => return compile_error(&message, span),
+    };
+    let kansuji = match number_value(&literal) {
+        Ok(kansuji) => kansuji,
+        Err(message) => return compile_error(&message, span),
+    };
+    let mut literal = Literal::string(&kansuji.to_string());
+    literal.set_span(span);
+    TokenStream::from(TokenTree::Literal(literal))
+}
+
+/// 入力が一つのリテラルであることを確かめ、その表記と位置を返す
+fn single_literal(input: TokenStream) -> Result<(String, Span), (String, Span)> {
+    let mut tokens = input.into_iter();
+    let token = match tokens.next() {
+        // macro_rules!から渡された場合は区切りのないグループに包まれる
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::None => {
+            return single_literal(group.stream());
+        }
+        Some(token) => token,
+        None => return Err(("expected a literal".to_string(), Span::call_site())),
+    };
+    if let Some(extra) = tokens.next() {
+        return Err(("unexpected token".to_string(), extra.span()));
+    }
+    match token {
+        TokenTree::Literal(literal) => Ok((literal.to_string(), literal.span())),
+        token => Err(("expected a literal".to_string(), token.span())),
+    }
+}
+
+/// 文字列リテラルの値を返す
+///
+/// エスケープを含むものは扱わない。
+fn string_value(literal: &str) -> Option<String> {
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let raw = &raw[hashes..raw.len() - hashes];
+        return raw
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .map(|s| s.to_string());
+    }
+    let s = literal.strip_prefix('"')?.strip_suffix('"')?;
+    if s.contains('\\') {
+        return None;
+    }
+    Some(s.to_string())
+}
+
+/// 整数または小数のリテラルを漢数字にする
+///
+/// 整数は`0x`・`0o`・`0b`で始まる16進・8進・2進のものも読む。
+/// 10の24乗以上の値は丸めずにエラーとする。
+fn number_value(literal: &str) -> Result<Kansuji, String> {
+    let s = literal.replace('_', "");
+    let (radix, s) = match s.get(..2) {
+        Some("0x") => (16, &s[2..]),
+        Some("0o") => (8, &s[2..]),
+        Some("0b") => (2, &s[2..]),
+        _ => (10, &s[..]),
+    };
+    // 16進の`f32`などは数字であり、小数の接尾辞は10進にだけ付く
+    let suffix = [
+        "u128", "usize", "u64", "u32", "u16", "u8", "i128", "isize", "i64", "i32", "i16", "i8",
+        "f64", "f32",
+    ]
+    .iter()
+    .filter(|suffix| radix == 10 || !suffix.starts_with('f'))
+    .find(|suffix| s.ends_with(*suffix));
+    let s = match suffix {
+        Some(suffix) => &s[..s.len() - suffix.len()],
+        None => s,
+    };
+    let expected = || "expected an integer or float literal".to_string();
+    let exponent = radix == 10 && s.contains(|c| c == 'e' || c == 'E');
+    if s.contains('.') || exponent || suffix.map_or(false, |suffix| suffix.starts_with('f')) {
+        let n = s.parse::<f64>().map_err(|_| expected())?;
+        Kansuji::try_from_f64(n).map_err(|e| e.to_string())
+    } else {
+        match u128::from_str_radix(s, radix) {
+            Ok(n) if n < KANSUJI_LIMIT => Ok(Kansuji::from(n)),
+            Ok(_) => Err(KansujiError::TooLarge.to_string()),
+            // u128にも収まらない整数
+            Err(_) if !s.is_empty() && s.chars().all(|c| c.is_digit(radix)) => {
+                Err(KansujiError::TooLarge.to_string())
+            }
+            Err(_) => Err(expected()),
+        }
+    }
+}
+
+/// `compile_error!`の呼び出しを作る
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut literal = Literal::string(message);
+    literal.set_span(span);
+    let tokens = vec![
+        TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+        TokenTree::Ident(Ident::new("core", span)),
+        TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+        TokenTree::Group(Group::new(
+            Delimiter::Parenthesis,
+            TokenStream::from(TokenTree::Literal(literal)),
+        )),
+    ];
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
@@ -0,0 +1,51 @@
+use kansuji::Kansuji;
+use kansuji_macros::{kansuji, kansuji_str};
+use std::convert::TryFrom;
+
+const SANBYAKU: Kansuji = kansuji!("三百");
+
+#[test]
+fn check_kansuji_macro_1() {
+    assert_eq!(SANBYAKU, Kansuji::from(300_u16));
+    assert_eq!(kansuji!("参萬伍阡"), Kansuji::from(35000_u32));
+    assert_eq!(
+        kansuji!("一二分三厘"),
+        Kansuji::try_from("一二分三厘").unwrap()
+    );
+    assert_eq!(kansuji!(r"3万5千"), Kansuji::from(35000_u32));
+    assert_eq!(kansuji!(r#"零"#), Kansuji::default());
+}
+
+macro_rules! wrap {
+    ($e:expr) => {
+        kansuji!($e)
+    };
+}
+
+#[test]
+fn check_kansuji_macro_2() {
+    assert_eq!(wrap!("十"), Kansuji::from(10_u8));
+}
+
+#[test]
+fn check_kansuji_str_1() {
+    const LABEL: &str = kansuji_str!(320);
+    assert_eq!(LABEL, "三百二十");
+    assert_eq!(kansuji_str!(1_0000_u32), "一万");
+    assert_eq!(kansuji_str!(0), "零");
+    assert_eq!(kansuji_str!(1.5), "一五分");
+    assert_eq!(kansuji_str!(1e4), "一万");
+    assert_eq!(kansuji_str!(0x10), "十六");
+    assert_eq!(kansuji_str!(0xff_u8), "二百五十五");
+    assert_eq!(kansuji_str!(0x1f32), "七千九百八十六");
+    assert_eq!(kansuji_str!(0o17), "十五");
+    assert_eq!(kansuji_str!(0b1010), "十");
+}
+
+#[test]
+fn check_kansuji_str_2() {
+    assert_eq!(
+        kansuji_str!(9999_9999_9999_9999_9999_9999_u128),
+        "九千九百九十九垓九千九百九十九京九千九百九十九兆九千九百九十九億九千九百九十九万九千九百九十九"
+    );
+}
@@ -0,0 +1,39 @@
+"""漢数字と数字の相互変換のためのライブラリ"""
+
+from decimal import Decimal
+from typing import Callable, Iterator, Tuple, Union
+
+class KansujiError(ValueError): ...
+class ParseError(KansujiError): ...
+class UnexpectedCharError(KansujiError): ...
+class UnexpectedEndError(KansujiError): ...
+class TooLargeError(KansujiError): ...
+class OutOfRangeError(KansujiError): ...
+
+class Match:
+    """見つかった漢数字（位置は文字単位）"""
+
+    @property
+    def start(self) -> int: ...
+    @property
+    def end(self) -> int: ...
+    @property
+    def text(self) -> str: ...
+    @property
+    def value(self) -> Union[int, Decimal]: ...
+    def span(self) -> Tuple[int, int]: ...
+
+class Matches(Iterator[Match]):
+    def __iter__(self) -> "Matches": ...
+    def __next__(self) -> Match: ...
+
+def parse(s: str) -> Union[int, Decimal]: ...
+def format(
+    value: Union[int, float, Decimal, str],
+    *,
+    daiji: bool = False,
+    explicit_one: bool = False,
+) -> str: ...
+def reading(s: str) -> str: ...
+def find_iter(text: str) -> Matches: ...
+def replace_all(text: str, repl: Union[str, Callable[[Match], str]]) -> str: ...
@@ -0,0 +1,284 @@
+//! # 概要
+//!
+//! [`kansuji`]をPythonから使うためのバインディングである。
+//! `maturin`でビルドすると、Pythonの`kansuji`モジュールになる。
+//!
+//! ```sh
+//! maturin develop -m kansuji-python/Cargo.toml
+//! ```
+//!
+//! ```python
+//! import kansuji
+//!
+//! kansuji.parse("一万二千三百四十五")  # 12345
+//! kansuji.format(35000, daiji=True)  # "参萬伍阡"
+//! kansuji.replace_all("定価三百二十円", lambda m: str(m.value))  # "定価320円"
+//! ```
+//!
+//! 漢数字として読めない場合は、[`kansuji::KansujiError`]に対応する`kansuji.KansujiError`の派生クラスを送出する。
+//! `kansuji.KansujiError`は`ValueError`を継承している。
+
+use kansuji::{JosuushiTable, Kansuji, KansujiFormat};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyFloat, PyInt, PyString};
+use std::convert::TryFrom;
+
+create_exception!(
+    kansuji,
+    KansujiError,
+    PyValueError,
+    "漢数字として読めない場合などに送出する例外の基底クラス"
+);
+create_exception!(kansuji, ParseError, KansujiError, "漢数字として読めない");
+create_exception!(
+    kansuji,
+    UnexpectedCharError,
+    KansujiError,
+    "予期しない文字がある"
+);
+create_exception!(
+    kansuji,
+    UnexpectedEndError,
+    KansujiError,
+    "予期しない位置で終わっている"
+);
+create_exception!(
+    kansuji,
+    TooLargeError,
+    KansujiError,
+    "扱える範囲を超えている"
+);
+create_exception!(kansuji, OutOfRangeError, KansujiError, "値が範囲外である");
+
+/// [`kansuji::KansujiError`]を対応するPythonの例外にする
+fn to_py_err(e: kansuji::KansujiError) -> PyErr {
+    let message = e.to_string();
+    match e {
+        kansuji::KansujiError::ParseError => ParseError::new_err(message),
+        kansuji::KansujiError::UnexpectedChar(_) => UnexpectedCharError::new_err(message),
+        kansuji::KansujiError::UnexpectedEnd => UnexpectedEndError::new_err(message),
+        kansuji::KansujiError::TooLarge => TooLargeError::new_err(message),
+        kansuji::KansujiError::OutOfRange => OutOfRangeError::new_err(message),
+    }
+}
+
+/// 値をPythonのオブジェクトにする
+///
+/// 小数部がない場合は`int`、ある場合は誤差のないよう`decimal.Decimal`とする。
+fn to_py_value(py: Python<'_>, kansuji: Kansuji) -> PyResult<Bound<'_, PyAny>> {
+    if kansuji.to_mou() % 1000 == 0 {
+        return Ok(kansuji.to_u128().into_pyobject(py)?.into_any());
+    }
+    py.import("decimal")?
+        .getattr("Decimal")?
+        .call1((kansuji.to_decimal_string(),))
+}
+
+/// `float`を漢数字にする
+///
+/// `str`と同じく扱うため、`1.2345`のように小数点以下が三桁を超える場合は切り捨てずにエラーとする。
+fn decimal_from_f64(n: f64) -> Result<Kansuji, kansuji::KansujiError> {
+    Kansuji::try_from_f64(n)?;
+    Kansuji::parse_decimal(&n.to_string())
+}
+
+/// 漢数字を読む
+///
+/// 小数部がない場合は`int`、ある場合は`decimal.Decimal`を返す。
+#[pyfunction]
+fn parse<'py>(py: Python<'py>, s: &str) -> PyResult<Bound<'py, PyAny>> {
+    let kansuji = Kansuji::try_from(s).map_err(to_py_err)?;
+    to_py_value(py, kansuji)
+}
+
+/// `int`・`float`・`decimal.Decimal`・十進の文字列を漢数字にする
+///
+/// 小数点以下は三桁までとし、それを超える場合は`float`でも`str`でも`ParseError`を送出する。
+#[pyfunction]
+#[pyo3(signature = (value, *, daiji = false, explicit_one = false))]
+fn format(value: &Bound<'_, PyAny>, daiji: bool, explicit_one: bool) -> PyResult<String> {
+    let kansuji = if value.is_instance_of::<PyBool>() {
+        return Err(PyTypeError::new_err(
+            "expected a number or string, not bool",
+        ));
+    } else if value.is_instance_of::<PyInt>() {
+        if value.lt(0)? {
+            return Err(to_py_err(kansuji::KansujiError::OutOfRange));
+        }
+        let n: u128 = value
+            .extract()
+            .map_err(|_| to_py_err(kansuji::KansujiError::TooLarge))?;
+        if n >= kansuji::KANSUJI_LIMIT {
+            return Err(to_py_err(kansuji::KansujiError::TooLarge));
+        }
+        Kansuji::from(n)
+    } else if value.is_instance_of::<PyFloat>() {
+        decimal_from_f64(value.extract()?).map_err(to_py_err)?
+    } else if value.is_instance_of::<PyString>() {
+        Kansuji::parse_decimal(&value.extract::<String>()?).map_err(to_py_err)?
+    } else if value.is_instance(&value.py().import("decimal")?.getattr("Decimal")?)? {
+        Kansuji::parse_decimal(&value.str()?.extract::<String>()?).map_err(to_py_err)?
+    } else {
+        return Err(PyTypeError::new_err(
+            "expected int, float, decimal.Decimal or str",
+        ));
+    };
+    let format = KansujiFormat {
+        daiji,
+        explicit_one,
+    };
+    Ok(kansuji.to_string_with(&format))
+}
+
+/// 漢数字の読みをひらがなで返す
+#[pyfunction]
+fn reading(s: &str) -> PyResult<String> {
+    Kansuji::try_from(s)
+        .map(|kansuji| kansuji.reading())
+        .map_err(to_py_err)
+}
+
+/// 見つかった漢数字
+///
+/// 位置はPythonの文字列と同じく文字単位で数える。
+#[pyclass(module = "kansuji", name = "Match", frozen)]
+struct PyMatch {
+    /// 開始位置
+    #[pyo3(get)]
+    start: usize,
+    /// 終了位置
+    #[pyo3(get)]
+    end: usize,
+    /// 元の表記
+    #[pyo3(get)]
+    text: String,
+    kansuji: Kansuji,
+}
+
+#[pymethods]
+impl PyMatch {
+    /// 値（`int`または`decimal.Decimal`）
+    #[getter]
+    fn value<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py_value(py, self.kansuji)
+    }
+
+    /// `(start, end)`を返す
+    fn span(&self) -> (usize, usize) {
+        (self.start, self.end)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<kansuji.Match span=({}, {}), text='{}'>",
+            self.start, self.end, self.text
+        )
+    }
+}
+
+/// [`find_iter`]が返すイテレータ
+#[pyclass(module = "kansuji", name = "Matches")]
+struct PyMatches {
+    text: String,
+    /// 次に探し始める位置（バイト）
+    pos: usize,
+    /// `pos`までの文字数
+    char_pos: usize,
+    /// 一文字だけの漢数字の後ろに続く助数詞の一覧
+    table: JosuushiTable,
+}
+
+impl PyMatches {
+    fn new(text: String) -> Self {
+        PyMatches {
+            text,
+            pos: 0,
+            char_pos: 0,
+            table: JosuushiTable::default(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyMatches {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> Option<PyMatch> {
+        let rest = &self.text[self.pos..];
+        let m = self.table.find_iter(rest).next()?;
+        let start = self.char_pos + rest[..m.start()].chars().count();
+        let end = start + m.as_str().chars().count();
+        let found = PyMatch {
+            start,
+            end,
+            text: m.as_str().to_string(),
+            kansuji: m.kansuji(),
+        };
+        self.pos += m.end();
+        self.char_pos = end;
+        Some(found)
+    }
+}
+
+/// 文字列の中の漢数字を先頭から順に返す
+#[pyfunction]
+fn find_iter(text: String) -> PyMatches {
+    PyMatches::new(text)
+}
+
+/// 文字列の中の漢数字をすべて置き換える
+///
+/// `repl`には置き換える文字列か、`Match`を受け取って文字列を返す関数を渡す。
+#[pyfunction]
+fn replace_all(py: Python<'_>, text: String, repl: &Bound<'_, PyAny>) -> PyResult<String> {
+    let fixed = if repl.is_instance_of::<PyString>() {
+        Some(repl.extract::<String>()?)
+    } else if repl.is_callable() {
+        None
+    } else {
+        return Err(PyTypeError::new_err("repl must be a str or callable"));
+    };
+    let mut replaced = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut matches = PyMatches::new(text.clone());
+    while let Some(found) = matches.__next__() {
+        // matches.posは今見つけた漢数字の終了位置（バイト）を指している
+        let end = matches.pos;
+        let start = end - found.text.len();
+        replaced.push_str(&text[last..start]);
+        match &fixed {
+            Some(s) => replaced.push_str(s),
+            None => {
+                let found = Bound::new(py, found)?;
+                replaced.push_str(&repl.call1((found,))?.extract::<String>()?);
+            }
+        }
+        last = end;
+    }
+    replaced.push_str(&text[last..]);
+    Ok(replaced)
+}
+
+/// 漢数字と数字の相互変換のためのライブラリ
+#[pymodule(name = "kansuji")]
+pub fn kansuji_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add_function(wrap_pyfunction!(parse, m)?)?;
+    m.add_function(wrap_pyfunction!(format, m)?)?;
+    m.add_function(wrap_pyfunction!(reading, m)?)?;
+    m.add_function(wrap_pyfunction!(find_iter, m)?)?;
+    m.add_function(wrap_pyfunction!(replace_all, m)?)?;
+    m.add_class::<PyMatch>()?;
+    m.add_class::<PyMatches>()?;
+    m.add("KansujiError", py.get_type::<KansujiError>())?;
+    m.add("ParseError", py.get_type::<ParseError>())?;
+    m.add("UnexpectedCharError", py.get_type::<UnexpectedCharError>())?;
+    m.add("UnexpectedEndError", py.get_type::<UnexpectedEndError>())?;
+    m.add("TooLargeError", py.get_type::<TooLargeError>())?;
+    m.add("OutOfRangeError", py.get_type::<OutOfRangeError>())?;
+    Ok(())
+}
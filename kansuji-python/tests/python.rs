@@ -0,0 +1,103 @@
+//! Pythonのインタプリタにモジュールを読み込み、Pythonのコードから呼び出す
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::wrap_pymodule;
+use std::ffi::CStr;
+
+fn run(code: &CStr) {
+    Python::attach(|py| {
+        let globals = PyDict::new(py);
+        globals
+            .set_item(
+                "kansuji",
+                wrap_pymodule!(kansuji_python::kansuji_module)(py),
+            )
+            .unwrap();
+        globals
+            .set_item("decimal", py.import("decimal").unwrap())
+            .unwrap();
+        if let Err(e) = py.run(code, Some(&globals), None) {
+            e.print(py);
+            panic!("python code failed");
+        }
+    });
+}
+
+#[test]
+fn check_parse_1() {
+    run(c"
+assert kansuji.parse('一万二千三百四十五') == 12345
+assert type(kansuji.parse('三百')) is int
+assert kansuji.parse('三分五厘') == decimal.Decimal('0.35')
+assert kansuji.parse('一万二千三百四十五六分') == decimal.Decimal('12345.6')
+assert kansuji.reading('三百') == 'さんびゃく'
+");
+}
+
+#[test]
+fn check_parse_2() {
+    run(c"
+try:
+    kansuji.parse('三百円')
+    assert False
+except kansuji.UnexpectedCharError as e:
+    assert str(e) == 'unexpected char: 円'
+assert issubclass(kansuji.UnexpectedCharError, kansuji.KansujiError)
+assert issubclass(kansuji.KansujiError, ValueError)
+");
+}
+
+#[test]
+fn check_format_1() {
+    run(c"
+assert kansuji.format(35000, daiji=True) == '参萬伍阡'
+assert kansuji.format(1000, explicit_one=True) == '一千'
+assert kansuji.format(1.5) == kansuji.format('1.5')
+assert kansuji.format(decimal.Decimal('320.05')) == kansuji.format('320.05')
+for value, error in [(-1, kansuji.OutOfRangeError), (10**24, kansuji.TooLargeError),
+                     (2**200, kansuji.TooLargeError), (float('nan'), kansuji.OutOfRangeError),
+                     ('1.2345', kansuji.ParseError), (1.2345, kansuji.ParseError), (True, TypeError), (None, TypeError)]:
+    try:
+        kansuji.format(value)
+        assert False, value
+    except error:
+        pass
+");
+}
+
+#[test]
+fn check_find_iter_1() {
+    run(c"
+text = '定価三百二十円、在庫は1万2千個'
+matches = list(kansuji.find_iter(text))
+assert [m.text for m in matches] == ['三百二十', '1万2千']
+assert [m.value for m in matches] == [320, 12000]
+assert [text[m.start:m.end] for m in matches] == ['三百二十', '1万2千']
+assert matches[0].span() == (2, 6)
+assert repr(matches[0]) == \"<kansuji.Match span=(2, 6), text='三百二十'>\"
+assert list(kansuji.find_iter('2024年')) == []
+");
+}
+
+#[test]
+fn check_replace_all_1() {
+    run(c"
+text = '定価三百二十円、在庫は1万2千個'
+assert kansuji.replace_all(text, lambda m: str(m.value)) == '定価320円、在庫は12000個'
+assert kansuji.replace_all(text, '*') == '定価*円、在庫は*個'
+assert kansuji.replace_all('千葉県で一石二鳥', '*') == '千葉県で一石二鳥'
+def fail(m):
+    raise KeyError(m.text)
+try:
+    kansuji.replace_all(text, fail)
+    assert False
+except KeyError:
+    pass
+try:
+    kansuji.replace_all(text, 1)
+    assert False
+except TypeError:
+    pass
+");
+}
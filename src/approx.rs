@@ -0,0 +1,280 @@
+//! 数百・十数人・約三万・百余・三万強のような概数の解析を行う
+//!
+//! 概数は取りうる値の下限と上限（どちらも含む）で表す。
+//! 約・およそ・ほど・前後・強・弱・足らず・余は、基準の数の一番下の位を幅の目安とする。
+//!
+//! ```
+//! use kansuji::Approx;
+//!
+//! let approx = Approx::try_from("数百万").unwrap();
+//! assert_eq!(u128::from(approx.lower()), 2_000_000);
+//! assert_eq!(u128::from(approx.upper()), 9_999_999);
+//!
+//! let approx = Approx::try_from("十数人").unwrap();
+//! assert_eq!(u128::from(approx.lower()), 11);
+//! assert_eq!(u128::from(approx.upper()), 19);
+//! assert_eq!(approx.counter(), Some("人"));
+//!
+//! let approx = Approx::try_from("約三万").unwrap();
+//! assert_eq!(u128::from(approx.lower()), 25_000);
+//! assert_eq!(u128::from(approx.upper()), 35_000);
+//! ```
+
+use super::{is_numeral_char, Kansuji, KansujiError};
+use alloc::string::{String, ToString};
+use core::convert::TryFrom;
+use core::fmt;
+
+/// 概数
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Approx {
+    下限: Kansuji,
+    上限: Kansuji,
+    助数詞: Option<String>,
+}
+
+impl Approx {
+    /// 下限と上限から概数を作る
+    pub fn new(lower: Kansuji, upper: Kansuji) -> Self {
+        Approx {
+            下限: lower,
+            上限: upper,
+            助数詞: None,
+        }
+    }
+
+    /// 取りうる値の下限を返す
+    pub fn lower(&self) -> Kansuji {
+        self.下限
+    }
+
+    /// 取りうる値の上限を返す
+    pub fn upper(&self) -> Kansuji {
+        self.上限
+    }
+
+    /// 数の後ろに付いた助数詞を返す
+    pub fn counter(&self) -> Option<&str> {
+        self.助数詞.as_deref()
+    }
+
+    /// 幅を持たない（下限と上限が等しい）かどうかを返す
+    pub fn is_exact(&self) -> bool {
+        self.下限 == self.上限
+    }
+}
+
+impl TryFrom<String> for Approx {
+    type Error = KansujiError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_approx(&value)
+    }
+}
+
+impl TryFrom<&String> for Approx {
+    type Error = KansujiError;
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        parse_approx(value)
+    }
+}
+
+impl TryFrom<&str> for Approx {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_approx(value)
+    }
+}
+
+/// 数の前に付いて概数を表す語
+const APPROX_PREFIXES: [&str; 3] = ["約", "およそ", "凡そ"];
+
+/// 数の後ろに付いて概数を表す語
+const APPROX_SUFFIXES: [&str; 7] = ["余り", "余", "強", "弱", "ほど", "前後", "足らず"];
+
+/// 一番下の位の大きさを毛（千分の一）を単位として返す
+fn lowest_place(mou: u128) -> u128 {
+    if mou == 0 {
+        return 1000;
+    }
+    let mut place = 1;
+    while mou % (place * 10) == 0 {
+        place *= 10;
+    }
+    place
+}
+
+/// `数`を含む数の下限と上限を毛を単位として返す
+///
+/// 先頭の`数`は二〜九、それ以外の`数`（十数など）は一〜九とし、`数`より下の位は任意とする。
+fn suu_bounds(n: &str) -> Result<(u128, u128), KansujiError> {
+    if n.matches('数').count() > 1 {
+        return Err(KansujiError::ParseError);
+    }
+    let with = |d: &str| Kansuji::try_from(n.replacen('数', d, 1)).map(Kansuji::to_mou);
+    let lower = with(if n.starts_with('数') { "二" } else { "一" })?;
+    let nine = with("九")?;
+    let place = nine - with("八")?;
+    if place < 1000 {
+        return Err(KansujiError::ParseError);
+    }
+    Ok((lower, nine + place - 1000))
+}
+
+fn parse_approx(s: &str) -> Result<Approx, KansujiError> {
+    let (prefix, s) = APPROX_PREFIXES
+        .iter()
+        .find_map(|p| s.strip_prefix(p).map(|s| (Some(*p), s)))
+        .unwrap_or((None, s));
+    let i = s
+        .char_indices()
+        .find(|(_, c)| !is_numeral_char(*c) && *c != '数')
+        .map_or(s.len(), |(i, _)| i);
+    let (n, rest) = s.split_at(i);
+    if n.is_empty() {
+        return Err(rest
+            .chars()
+            .next()
+            .map_or(KansujiError::UnexpectedEnd, KansujiError::UnexpectedChar));
+    }
+    // 百余人のように助数詞の前に付くものと、百人余のように後ろに付くものがある
+    let (suffix, rest) = match APPROX_SUFFIXES
+        .iter()
+        .find_map(|m| rest.strip_prefix(m).map(|r| (*m, r)))
+    {
+        Some((m, r)) => (Some(m), r),
+        None => match APPROX_SUFFIXES
+            .iter()
+            .find_map(|m| rest.strip_suffix(m).map(|r| (*m, r)))
+        {
+            Some((m, r)) => (Some(m), r),
+            None => (None, rest),
+        },
+    };
+    let counter = if rest.is_empty() {
+        None
+    } else {
+        Some(rest.to_string())
+    };
+
+    let (lower, upper) = if n.contains('数') {
+        if prefix.is_some() || suffix.is_some() {
+            return Err(KansujiError::ParseError);
+        }
+        suu_bounds(n)?
+    } else {
+        let x = Kansuji::try_from(n)?.to_mou();
+        let place = lowest_place(x);
+        let half = place / 2;
+        // 値を含まない端を表すための最小の幅
+        let step = if place >= 1000 { 1000 } else { 1 };
+        match (prefix, suffix) {
+            (None, None) => (x, x),
+            (Some(_), None) | (Some(_), Some("ほど")) | (Some(_), Some("前後")) => {
+                (x.saturating_sub(half), x + half)
+            }
+            (Some(_), Some(_)) => return Err(KansujiError::ParseError),
+            (None, Some("ほど")) | (None, Some("前後")) => (x.saturating_sub(half), x + half),
+            (None, Some("強")) => (x, x + half),
+            (None, Some("弱")) => (x.saturating_sub(half), x),
+            (None, Some("足らず")) => (x.saturating_sub(half), x.saturating_sub(step)),
+            (None, Some(_)) => {
+                if place <= step {
+                    return Err(KansujiError::ParseError);
+                }
+                (x + step, x + place - step)
+            }
+        }
+    };
+    Ok(Approx {
+        下限: Kansuji::from_mou(lower),
+        上限: Kansuji::from_mou(upper),
+        助数詞: counter,
+    })
+}
+
+#[test]
+fn check_parse_approx_1() {
+    let v = [
+        ("数百万", 2_000_000, 9_999_999),
+        ("数百", 200, 999),
+        ("数十", 20, 99),
+        ("数", 2, 9),
+        ("十数", 11, 19),
+        ("十数万", 110_000, 199_999),
+        ("数十万", 200_000, 999_999),
+        ("百数十", 110, 199),
+        ("二十数", 21, 29),
+        ("約三万", 25_000, 35_000),
+        ("およそ二百", 150, 250),
+        ("百余", 101, 199),
+        ("三万余", 30_001, 39_999),
+        ("三万強", 30_000, 35_000),
+        ("三万弱", 25_000, 30_000),
+        ("百ほど", 50, 150),
+        ("二十前後", 15, 25),
+        ("百足らず", 50, 99),
+        ("三百", 300, 300),
+    ];
+    for (s, lower, upper) in v.iter() {
+        let approx = parse_approx(s).unwrap();
+        assert_eq!(u128::from(approx.lower()), *lower);
+        assert_eq!(u128::from(approx.upper()), *upper);
+        assert_eq!(approx.counter(), None);
+    }
+}
+
+#[test]
+fn check_parse_approx_2() {
+    let approx = parse_approx("十数人").unwrap();
+    assert_eq!(approx.counter(), Some("人"));
+    let approx = parse_approx("百余人").unwrap();
+    assert_eq!(u128::from(approx.lower()), 101);
+    assert_eq!(approx.counter(), Some("人"));
+    let approx = parse_approx("三万人強").unwrap();
+    assert_eq!(u128::from(approx.upper()), 35_000);
+    assert_eq!(approx.counter(), Some("人"));
+    let approx = parse_approx("約百人ほど").unwrap();
+    assert_eq!(u128::from(approx.lower()), 50);
+    assert_eq!(approx.counter(), Some("人"));
+    let approx = parse_approx("約三").unwrap();
+    assert_eq!(f64::from(approx.lower()), 2.5);
+    assert_eq!(f64::from(approx.upper()), 3.5);
+}
+
+#[test]
+fn check_parse_approx_3() {
+    assert_eq!(parse_approx(""), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_approx("約"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_approx("人"), Err(KansujiError::UnexpectedChar('人')));
+    assert_eq!(parse_approx("数数"), Err(KansujiError::ParseError));
+    assert_eq!(parse_approx("約数百"), Err(KansujiError::ParseError));
+    assert_eq!(parse_approx("約百強"), Err(KansujiError::ParseError));
+    assert_eq!(parse_approx("三余"), Err(KansujiError::ParseError));
+}
+
+impl fmt::Display for Approx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_exact() {
+            write!(f, "{}", self.下限)?;
+        } else {
+            write!(f, "{}〜{}", self.下限, self.上限)?;
+        }
+        if let Some(counter) = &self.助数詞 {
+            write!(f, "{}", counter)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn check_approx_1() {
+    assert_eq!(
+        Approx::try_from("十数人").unwrap().to_string(),
+        "十一〜十九人"
+    );
+    assert_eq!(Approx::try_from("三百").unwrap().to_string(), "三百");
+    assert_eq!(
+        Approx::new(Kansuji::from(2_u8), Kansuji::from(3_u8)).to_string(),
+        "二〜三"
+    );
+}
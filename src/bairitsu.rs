@@ -0,0 +1,273 @@
+//! 三倍・半分・二割増し・三割引きのような倍率の解析と計算を行う
+//!
+//! `半`は単独では0.5を表し、`三倍半`のように単位の後ろに付いた場合はその単位の0.5を足す。
+//! 一時半や一年半のような時刻と期間の`半`は[`Jikoku`](crate::Jikoku)と[`Kikan`](crate::Kikan)で読む。
+//!
+//! ```
+//! use kansuji::{Bairitsu, Kansuji};
+//!
+//! let base = Kansuji::from(1000_u16);
+//! let bairitsu = Bairitsu::try_from("三倍半").unwrap();
+//! assert_eq!(u128::from(bairitsu.apply(base).unwrap()), 3500);
+//!
+//! let bairitsu = Bairitsu::try_from("二割増し").unwrap();
+//! assert_eq!(u128::from(bairitsu.apply(base).unwrap()), 1200);
+//! assert_eq!(f64::from(bairitsu.factor().unwrap()), 1.2);
+//!
+//! let bairitsu = Bairitsu::try_from("半分").unwrap();
+//! assert_eq!(u128::from(bairitsu.apply(base).unwrap()), 500);
+//! ```
+
+use super::{Buai, Kansuji, KansujiError, KANSUJI_LIMIT};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(all(test, feature = "alloc"))]
+use alloc::string::ToString;
+use core::convert::TryFrom;
+use core::fmt;
+
+/// 倍率
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Bairitsu {
+    /// 基準の数に掛ける（三倍・半分）
+    倍(Kansuji),
+    /// 基準の数に割合を足す（二割増し）
+    増し(Buai),
+    /// 基準の数から割合を引く（三割引き）
+    引き(Buai),
+    /// 基準の数から割合を引く（三割減）
+    減(Buai),
+}
+
+/// 歩合の一（十割）を毛で数えたもの
+const BUAI_ONE: u128 = 10000;
+
+/// 漢数字で表せる最大の値を毛で数えたもの
+const KANSUJI_MAX_MOU: u128 = KANSUJI_LIMIT * 1000 - 1;
+
+impl Bairitsu {
+    /// 基準の数に掛ける値を返す
+    ///
+    /// 二割増しは1.2、三割引きは0.7となる。[`apply`](Bairitsu::apply)はこの値を掛けたものと一致する。
+    /// 歩合の毛（一万分の一）が付いていて漢数字の毛（千分の一）までで表せない場合と、
+    /// 割合が十割を超えて引く場合は`OutOfRange`を返す。
+    pub fn factor(&self) -> Result<Kansuji, KansujiError> {
+        let ratio = match self {
+            Bairitsu::倍(n) => return Ok(*n),
+            Bairitsu::増し(b) => BUAI_ONE + b.to_mou() as u128,
+            Bairitsu::引き(b) | Bairitsu::減(b) => BUAI_ONE
+                .checked_sub(b.to_mou() as u128)
+                .ok_or(KansujiError::OutOfRange)?,
+        };
+        if ratio % 10 != 0 {
+            return Err(KansujiError::OutOfRange);
+        }
+        Ok(Kansuji::from_mou(ratio / 10))
+    }
+
+    /// 基準の数に倍率を適用する
+    ///
+    /// 毛より下の位は切り捨てる。十割を超えて引く場合は`OutOfRange`を返す。
+    pub fn apply(&self, base: Kansuji) -> Result<Kansuji, KansujiError> {
+        let base = base.to_mou();
+        let mou = match self {
+            Bairitsu::倍(n) => base.checked_mul(n.to_mou()).map(|n| n / 1000),
+            Bairitsu::増し(b) => base
+                .checked_mul(BUAI_ONE + b.to_mou() as u128)
+                .map(|n| n / BUAI_ONE),
+            Bairitsu::引き(b) | Bairitsu::減(b) => {
+                let ratio = BUAI_ONE
+                    .checked_sub(b.to_mou() as u128)
+                    .ok_or(KansujiError::OutOfRange)?;
+                base.checked_mul(ratio).map(|n| n / BUAI_ONE)
+            }
+        };
+        match mou {
+            Some(mou) if mou <= KANSUJI_MAX_MOU => Ok(Kansuji::from_mou(mou)),
+            _ => Err(KansujiError::TooLarge),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<String> for Bairitsu {
+    type Error = KansujiError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_bairitsu(&value)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<&String> for Bairitsu {
+    type Error = KansujiError;
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        parse_bairitsu(value)
+    }
+}
+
+impl TryFrom<&str> for Bairitsu {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_bairitsu(value)
+    }
+}
+
+/// 半を表す0.5
+fn han() -> Kansuji {
+    Kansuji::from_mou(500)
+}
+
+fn parse_bairitsu(s: &str) -> Result<Bairitsu, KansujiError> {
+    if s == "半分" || s == "半" {
+        return Ok(Bairitsu::倍(han()));
+    }
+    let (n, suffix) = ["倍半", "倍", "増し", "増", "引き", "引", "減"]
+        .iter()
+        .find_map(|suffix| s.strip_suffix(suffix).map(|n| (n, *suffix)))
+        .ok_or_else(|| match Kansuji::try_from(s) {
+            // 倍率を表す語がない
+            Ok(_) => KansujiError::UnexpectedEnd,
+            Err(e) => e,
+        })?;
+    if n.is_empty() {
+        return Err(KansujiError::UnexpectedEnd);
+    }
+    match suffix {
+        "倍半" => {
+            let n = Kansuji::try_from(n)?;
+            if n.has_fraction() {
+                return Err(KansujiError::ParseError);
+            }
+            match n.to_mou().checked_add(500) {
+                Some(mou) if mou <= KANSUJI_MAX_MOU => Ok(Bairitsu::倍(Kansuji::from_mou(mou))),
+                _ => Err(KansujiError::TooLarge),
+            }
+        }
+        "倍" => Ok(Bairitsu::倍(Kansuji::try_from(n)?)),
+        "増し" | "増" => Ok(Bairitsu::増し(Buai::try_from(n)?)),
+        "引き" | "引" => Ok(Bairitsu::引き(Buai::try_from(n)?)),
+        _ => Ok(Bairitsu::減(Buai::try_from(n)?)),
+    }
+}
+
+#[test]
+fn check_parse_bairitsu_1() {
+    let base = Kansuji::from(10000_u16);
+    let v = [
+        ("三倍", 30000),
+        ("三倍半", 35000),
+        ("十倍", 100000),
+        ("半分", 5000),
+        ("半", 5000),
+        ("二割増し", 12000),
+        ("二割増", 12000),
+        ("二割五分増し", 12500),
+        ("三割引き", 7000),
+        ("三割引", 7000),
+        ("五分引き", 9500),
+        ("三割減", 7000),
+        ("十割増し", 20000),
+        ("十割引き", 0),
+        ("20%増し", 12000),
+    ];
+    for (s, n) in v.iter() {
+        let bairitsu = parse_bairitsu(s).unwrap();
+        assert_eq!(u128::from(bairitsu.apply(base).unwrap()), *n);
+    }
+}
+
+#[test]
+fn check_parse_bairitsu_2() {
+    assert_eq!(parse_bairitsu("三"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_bairitsu(""), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_bairitsu("倍"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(
+        parse_bairitsu("三個"),
+        Err(KansujiError::UnexpectedChar('個'))
+    );
+    assert_eq!(parse_bairitsu("三五分倍半"), Err(KansujiError::ParseError));
+    let max = "九千九百九十九垓九千九百九十九京九千九百九十九兆九千九百九十九億九千九百九十九万九千九百九十九倍半";
+    let bairitsu = parse_bairitsu(max).unwrap();
+    assert_eq!(
+        bairitsu,
+        Bairitsu::倍(Kansuji::from_mou(KANSUJI_MAX_MOU - 499))
+    );
+    let bairitsu = parse_bairitsu("十一割引き").unwrap();
+    assert_eq!(
+        bairitsu.apply(Kansuji::from(1_u8)),
+        Err(KansujiError::OutOfRange)
+    );
+    assert_eq!(bairitsu.factor(), Err(KansujiError::OutOfRange));
+    let bairitsu = parse_bairitsu("一万倍").unwrap();
+    assert_eq!(
+        bairitsu.apply(Kansuji::from(1_0000_0000_0000_0000_0000_u128)),
+        Err(KansujiError::TooLarge)
+    );
+}
+
+#[test]
+fn check_bairitsu_apply_1() {
+    let bairitsu = parse_bairitsu("三倍").unwrap();
+    assert_eq!(f64::from(bairitsu.apply(Kansuji::from(1.5)).unwrap()), 4.5);
+    let bairitsu = parse_bairitsu("半分").unwrap();
+    assert_eq!(f64::from(bairitsu.apply(Kansuji::from(3_u8)).unwrap()), 1.5);
+    let bairitsu = parse_bairitsu("三割引き").unwrap();
+    assert_eq!(bairitsu.factor(), Kansuji::try_from("七分"));
+}
+
+#[test]
+fn check_bairitsu_factor_1() {
+    let base = Kansuji::from(12345_u16);
+    for s in [
+        "三倍半",
+        "半分",
+        "二割増し",
+        "三割五分引き",
+        "一割二分五厘減",
+        "九千九百九十九割増し",
+    ] {
+        let bairitsu = parse_bairitsu(s).unwrap();
+        let factor = bairitsu.factor().unwrap();
+        let mou = base.to_mou() * factor.to_mou() / 1000;
+        assert_eq!(bairitsu.apply(base), Ok(Kansuji::from_mou(mou)));
+    }
+    let bairitsu = parse_bairitsu("二割五分五厘五毛増し").unwrap();
+    assert_eq!(bairitsu.factor(), Err(KansujiError::OutOfRange));
+    assert_eq!(
+        bairitsu.apply(Kansuji::from(10000_u16)),
+        Ok(Kansuji::from(12555_u16))
+    );
+}
+
+impl fmt::Display for Bairitsu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bairitsu::倍(n) if *n == han() => write!(f, "半分"),
+            Bairitsu::倍(n) if n.to_mou() % 1000 == 500 => {
+                write!(f, "{}倍半", Kansuji::from(u128::from(*n)))
+            }
+            Bairitsu::倍(n) => write!(f, "{}倍", n),
+            Bairitsu::増し(b) => write!(f, "{}増し", b),
+            Bairitsu::引き(b) => write!(f, "{}引き", b),
+            Bairitsu::減(b) => write!(f, "{}減", b),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn check_bairitsu_1() {
+    fn bairitsu_test_function((s, t): &(&str, &str)) {
+        assert_eq!(Bairitsu::try_from(*s).unwrap().to_string(), *t);
+    }
+
+    let v = [
+        ("三倍", "三倍"),
+        ("三倍半", "三倍半"),
+        ("半", "半分"),
+        ("二割増", "二割増し"),
+        ("三割引", "三割引き"),
+        ("三割減", "三割減"),
+    ];
+    v.iter().for_each(bairitsu_test_function);
+}
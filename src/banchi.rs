@@ -0,0 +1,236 @@
+//! 住所の番地（三丁目四番五号・一-二-三など）の解析と正規化を行う
+//!
+//! 丁目・番地・番・号の付いたもののほか、ハイフンや`の`で区切った略記、
+//! 漢数字と算用数字が混ざったもの、`一二三`のような位取り記数法で書かれたものに対応する。
+//!
+//! ```
+//! use kansuji::Banchi;
+//!
+//! let banchi = Banchi::try_from("三丁目四番五号").unwrap();
+//! assert_eq!(banchi.to_tuple(), (Some(3), Some(4), Some(5)));
+//!
+//! let banchi = Banchi::try_from("１-二-3").unwrap();
+//! assert_eq!(banchi.to_tuple(), (Some(1), Some(2), Some(3)));
+//! assert_eq!(banchi.to_string(), "一丁目二番三号");
+//!
+//! let banchi = Banchi::try_from("十二番地の一").unwrap();
+//! assert_eq!(banchi.to_tuple(), (None, Some(12), Some(1)));
+//! ```
+
+use super::{is_numeral_char, parse_integer, Kansuji, KansujiError};
+use alloc::string::String;
+#[cfg(test)]
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::convert::{From, TryFrom};
+use core::fmt;
+
+/// 住所の番地
+///
+/// 番地と番は同じものとして扱い、番地の後ろの枝番は号として扱う。
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+pub struct Banchi {
+    丁目: Option<u32>,
+    番: Option<u32>,
+    号: Option<u32>,
+}
+
+impl Banchi {
+    /// 丁目・番・号から番地を作る
+    pub fn new(chome: Option<u32>, ban: Option<u32>, gou: Option<u32>) -> Self {
+        Banchi {
+            丁目: chome,
+            番: ban,
+            号: gou,
+        }
+    }
+
+    /// 丁目を返す
+    pub fn chome(&self) -> Option<u32> {
+        self.丁目
+    }
+
+    /// 番（番地）を返す
+    pub fn ban(&self) -> Option<u32> {
+        self.番
+    }
+
+    /// 号を返す
+    pub fn gou(&self) -> Option<u32> {
+        self.号
+    }
+
+    /// 丁目・番・号の組を返す
+    pub fn to_tuple(&self) -> (Option<u32>, Option<u32>, Option<u32>) {
+        (self.丁目, self.番, self.号)
+    }
+}
+
+impl TryFrom<String> for Banchi {
+    type Error = KansujiError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_banchi(&value)
+    }
+}
+
+impl TryFrom<&String> for Banchi {
+    type Error = KansujiError;
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        parse_banchi(value)
+    }
+}
+
+impl TryFrom<&str> for Banchi {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_banchi(value)
+    }
+}
+
+/// 丁目・番・号の位置を表す接尾辞
+const BANCHI_SUFFIXES: [(&str, usize); 4] = [("丁目", 0), ("番地", 1), ("番", 1), ("号", 2)];
+
+/// 番地の区切りに使われる文字かどうかを判定する
+fn is_banchi_separator(c: char) -> bool {
+    matches!(c, '-' | '－' | '‐' | '−' | '―' | 'ー' | 'の' | 'ノ')
+}
+
+fn parse_banchi(s: &str) -> Result<Banchi, KansujiError> {
+    // 数と、接尾辞が付いている場合はその位置を並べる
+    let mut items: Vec<(u32, Option<usize>)> = Vec::new();
+    let mut rest = s;
+    loop {
+        let i = rest
+            .char_indices()
+            .find(|(_, c)| !is_numeral_char(*c))
+            .map_or(rest.len(), |(i, _)| i);
+        let (n, r) = rest.split_at(i);
+        if n.is_empty() {
+            return Err(r
+                .chars()
+                .next()
+                .map_or(KansujiError::UnexpectedEnd, KansujiError::UnexpectedChar));
+        }
+        let n = u32::try_from(parse_integer(n)?).map_err(|_| KansujiError::TooLarge)?;
+        rest = r;
+        let mut slot = None;
+        for (suffix, i) in BANCHI_SUFFIXES.iter() {
+            if let Some(r) = rest.strip_prefix(suffix) {
+                slot = Some(*i);
+                rest = r;
+                break;
+            }
+        }
+        items.push((n, slot));
+        match rest.chars().next() {
+            None => break,
+            Some(c) if is_banchi_separator(c) => rest = &rest[c.len_utf8()..],
+            Some(c) if slot.is_some() && is_numeral_char(c) => (),
+            Some(c) => return Err(KansujiError::UnexpectedChar(c)),
+        }
+    }
+
+    let mut slots = [None; 3];
+    let mut next = 0;
+    let mut i = 0;
+    while i < items.len() {
+        if let (n, Some(slot)) = items[i] {
+            if slot < next {
+                return Err(KansujiError::ParseError);
+            }
+            slots[slot] = Some(n);
+            next = slot + 1;
+            i += 1;
+            continue;
+        }
+        // 接尾辞の付いていない数の並びの位置を決める
+        let len = items[i..].iter().take_while(|(_, s)| s.is_none()).count();
+        let start = match items.get(i + len) {
+            Some((_, Some(slot))) if *slot >= len => slot - len,
+            Some(_) => return Err(KansujiError::ParseError),
+            // 一-二-三は丁目・番・号、一-二は番・号とする
+            None if next == 0 && len < 3 => 1,
+            None => next,
+        };
+        if start < next || start + len > 3 {
+            return Err(KansujiError::ParseError);
+        }
+        for (j, (n, _)) in items[i..i + len].iter().enumerate() {
+            slots[start + j] = Some(*n);
+        }
+        next = start + len;
+        i += len;
+    }
+    Ok(Banchi {
+        丁目: slots[0],
+        番: slots[1],
+        号: slots[2],
+    })
+}
+
+#[test]
+fn check_parse_banchi_1() {
+    let v = [
+        ("三丁目四番五号", (Some(3), Some(4), Some(5))),
+        ("三丁目四番地", (Some(3), Some(4), None)),
+        ("十二番地の一", (None, Some(12), Some(1))),
+        ("五番地二", (None, Some(5), Some(2))),
+        ("一-二-三", (Some(1), Some(2), Some(3))),
+        ("1-2-3", (Some(1), Some(2), Some(3))),
+        ("１－２－３", (Some(1), Some(2), Some(3))),
+        ("４ー５", (None, Some(4), Some(5))),
+        ("二丁目4-5", (Some(2), Some(4), Some(5))),
+        ("二丁目十一-3", (Some(2), Some(11), Some(3))),
+        ("一二三番地", (None, Some(123), None)),
+        ("二〇三-十五", (None, Some(203), Some(15))),
+        ("7", (None, Some(7), None)),
+        ("三-四番", (Some(3), Some(4), None)),
+    ];
+    for (s, t) in v.iter() {
+        assert_eq!(parse_banchi(s).map(|b| b.to_tuple()), Ok(*t));
+    }
+}
+
+#[test]
+fn check_parse_banchi_2() {
+    assert_eq!(parse_banchi(""), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_banchi("一-"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(
+        parse_banchi("丁目"),
+        Err(KansujiError::UnexpectedChar('丁'))
+    );
+    assert_eq!(
+        parse_banchi("三丁目四階"),
+        Err(KansujiError::UnexpectedChar('階'))
+    );
+    assert_eq!(parse_banchi("四番三丁目"), Err(KansujiError::ParseError));
+    assert_eq!(parse_banchi("1-2-3-4"), Err(KansujiError::ParseError));
+    assert_eq!(parse_banchi("1-2-3丁目"), Err(KansujiError::ParseError));
+}
+
+impl fmt::Display for Banchi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (n, unit) in [(self.丁目, "丁目"), (self.番, "番"), (self.号, "号")] {
+            if let Some(n) = n {
+                write!(f, "{}{}", Kansuji::from(n), unit)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn check_banchi_1() {
+    assert_eq!(
+        Banchi::new(Some(3), Some(4), Some(5)).to_string(),
+        "三丁目四番五号"
+    );
+    assert_eq!(
+        Banchi::new(None, Some(12), Some(1)).to_string(),
+        "十二番一号"
+    );
+    assert_eq!(
+        Banchi::try_from("二〇三-十五").unwrap().to_string(),
+        "二百三番十五号"
+    );
+}
@@ -0,0 +1,300 @@
+//! 歩合（割・分・厘・毛）の解析と変換を行う
+//!
+//! 歩合では`分`は百分の一を表すため、十分の一を表す[`Kansuji`]の`分`とは意味が異なる。
+//! そのため「三分」は[`Kansuji`]としては0.3であるが、[`Buai`]としては0.03となる。
+//!
+//! ```
+//! use kansuji::{Buai, Kansuji};
+//!
+//! let buai = Buai::try_from("三割二分五厘").unwrap();
+//! assert_eq!(f64::from(buai), 0.325);
+//! assert_eq!(buai.to_percent().to_string(), "三十二五分");
+//!
+//! let buai = Buai::try_from("百パーセント").unwrap();
+//! assert_eq!(buai.to_string(), "十割");
+//!
+//! assert_eq!(f64::from(Buai::try_from("三分").unwrap()), 0.03);
+//! assert_eq!(f64::from(Kansuji::try_from("三分").unwrap()), 0.3);
+//! ```
+
+use super::{parse_keta, Kansuji, KansujiError, KansujiField, KansujiKeta};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(all(test, feature = "alloc"))]
+use alloc::string::ToString;
+use core::convert::{From, TryFrom};
+use core::fmt;
+
+/// 歩合
+///
+/// 割・分・厘・毛の各桁を持つ。割は0から9999までとする。
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Buai {
+    割: KansujiKeta,
+    分: KansujiField,
+    厘: KansujiField,
+    毛: KansujiField,
+}
+
+impl Default for Buai {
+    fn default() -> Self {
+        Buai {
+            割: KansujiKeta::default(),
+            分: KansujiField::零,
+            厘: KansujiField::零,
+            毛: KansujiField::零,
+        }
+    }
+}
+
+impl Buai {
+    /// 毛（一万分の一）を単位とした値を返す
+    pub(crate) fn to_mou(self) -> usize {
+        let mut n = Into::<usize>::into(self.割) * 1000;
+        n += self.分.to_int() as usize * 100;
+        n += self.厘.to_int() as usize * 10;
+        n += self.毛.to_int() as usize;
+        n
+    }
+
+    fn from_mou(n: usize) -> Self {
+        Buai {
+            割: KansujiKeta::from(n / 1000),
+            分: KansujiField::from_int(((n % 1000) / 100) as u8),
+            厘: KansujiField::from_int(((n % 100) / 10) as u8),
+            毛: KansujiField::from_int((n % 10) as u8),
+        }
+    }
+
+    /// 百分率に変換する
+    ///
+    /// 三割二分五厘は32.5パーセントであるため、`三十二五分`を表す[`Kansuji`]となる。
+    pub fn to_percent(&self) -> Kansuji {
+        Kansuji::from_mou(self.to_mou() as u128 * 10)
+    }
+
+    /// 百分率から変換する
+    ///
+    /// 歩合の最小単位は毛であるため、百分率の毛（0.001パーセント）の桁は切り捨てられる。
+    pub fn from_percent(percent: Kansuji) -> Result<Self, KansujiError> {
+        let n: u128 = percent.into();
+        if n >= 100000 {
+            return Err(KansujiError::TooLarge);
+        }
+        Ok(Buai::from_mou((percent.to_mou() / 10) as usize))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<String> for Buai {
+    type Error = KansujiError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_buai(&value)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<&String> for Buai {
+    type Error = KansujiError;
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        parse_buai(value)
+    }
+}
+
+impl TryFrom<&str> for Buai {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_buai(value)
+    }
+}
+
+fn parse_buai(s: &str) -> Result<Buai, KansujiError> {
+    for suffix in ["パーセント", "%", "％"] {
+        if let Some(s) = s.strip_suffix(suffix) {
+            return Buai::from_percent(Kansuji::try_from(s)?);
+        }
+    }
+    let mut chars = s.chars().peekable();
+    let mut buai = Buai::default();
+    let mut keta = 4_i8;
+    loop {
+        let group = parse_keta(&mut chars)?;
+        let kansuji_keta = KansujiKeta::from(group.unwrap_or(0) as usize);
+        if let Some(c) = chars.peek() {
+            match c {
+                // 割・分・厘・毛の前には数がなければならない
+                c if group.is_none() => return Err(KansujiError::UnexpectedChar(*c)),
+                '割' if keta > 3 => {
+                    buai = Buai {
+                        割: kansuji_keta,
+                        ..buai
+                    };
+                    chars.next();
+                    keta = 3;
+                }
+                '分' if keta > 2 && kansuji_keta.is_one_digit() => {
+                    buai = Buai {
+                        分: kansuji_keta.一,
+                        ..buai
+                    };
+                    chars.next();
+                    keta = 2;
+                }
+                '厘' if keta > 1 && kansuji_keta.is_one_digit() => {
+                    buai = Buai {
+                        厘: kansuji_keta.一,
+                        ..buai
+                    };
+                    chars.next();
+                    keta = 1;
+                }
+                '毛' if keta > 0 && kansuji_keta.is_one_digit() => {
+                    buai = Buai {
+                        毛: kansuji_keta.一,
+                        ..buai
+                    };
+                    chars.next();
+                    keta = 0;
+                }
+                c => return Err(KansujiError::UnexpectedChar(*c)),
+            }
+        } else if kansuji_keta.is_zero() {
+            break;
+        } else {
+            return Err(KansujiError::UnexpectedEnd);
+        }
+    }
+    Ok(buai)
+}
+
+#[test]
+fn check_parse_buai_1() {
+    let buai = parse_buai("三割二分五厘");
+    assert_eq!(
+        buai,
+        Ok(Buai {
+            割: KansujiKeta::from(3),
+            分: KansujiField::二,
+            厘: KansujiField::五,
+            毛: KansujiField::零,
+        })
+    );
+}
+
+#[test]
+fn check_parse_buai_2() {
+    assert_eq!(parse_buai("三分"), Ok(Buai::from_mou(300)));
+    assert_eq!(parse_buai("十割"), Ok(Buai::from_mou(10000)));
+    assert_eq!(parse_buai("五厘三毛"), Ok(Buai::from_mou(53)));
+}
+
+#[test]
+fn check_parse_buai_3() {
+    assert_eq!(parse_buai("百パーセント"), Ok(Buai::from_mou(10000)));
+    assert_eq!(parse_buai("三十二五分%"), Ok(Buai::from_mou(3250)));
+    assert_eq!(parse_buai("五％"), Ok(Buai::from_mou(500)));
+}
+
+#[test]
+fn check_parse_buai_4() {
+    assert_eq!(
+        parse_buai("二分三割"),
+        Err(KansujiError::UnexpectedChar('割'))
+    );
+    assert_eq!(parse_buai("十分"), Err(KansujiError::UnexpectedChar('分')));
+    assert_eq!(parse_buai("三割二"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_buai("割"), Err(KansujiError::UnexpectedChar('割')));
+    assert_eq!(
+        parse_buai("三割分"),
+        Err(KansujiError::UnexpectedChar('分'))
+    );
+}
+
+impl fmt::Display for Buai {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.to_mou() == 0 {
+            return write!(f, "零");
+        }
+        if !self.割.is_zero() {
+            if self.割.is_one() {
+                write!(f, "一割")?;
+            } else {
+                write!(f, "{}割", self.割)?;
+            }
+        }
+        if self.分 != KansujiField::零 {
+            write!(f, "{}分", self.分.to_str2())?;
+        }
+        if self.厘 != KansujiField::零 {
+            write!(f, "{}厘", self.厘.to_str2())?;
+        }
+        if self.毛 != KansujiField::零 {
+            write!(f, "{}毛", self.毛.to_str2())?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Buai> for f64 {
+    fn from(value: Buai) -> Self {
+        value.to_mou() as f64 / 10000.0
+    }
+}
+
+/// 比率から変換する
+///
+/// 負の数・`NaN`と、一万割以上になる値は`OutOfRange`とする。
+impl TryFrom<f64> for Buai {
+    type Error = KansujiError;
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if value.is_nan() || value < 0.0 {
+            return Err(KansujiError::OutOfRange);
+        }
+        // f64::roundはcoreにないため、0.5を足して切り捨てる
+        let n = value * 10000.0 + 0.5;
+        if n >= 10000000.0 {
+            return Err(KansujiError::OutOfRange);
+        }
+        Ok(Buai::from_mou(n as usize))
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn check_buai_1() {
+    fn buai_test_function(s: &str) {
+        let buai = Buai::try_from(s).unwrap();
+        assert_eq!(s, buai.to_string());
+        assert_eq!(Buai::try_from(f64::from(buai)), Ok(buai));
+    }
+
+    let v = [
+        "零",
+        "一割",
+        "三割二分五厘",
+        "十割",
+        "二分",
+        "一厘一毛",
+        "九割九分九厘九毛",
+    ];
+    v.iter().for_each(|s| buai_test_function(s));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn check_buai_2() {
+    let buai = Buai::try_from(0.285).unwrap();
+    assert_eq!(buai.to_string(), "二割八分五厘");
+    assert_eq!(buai.to_percent(), Kansuji::try_from("二十八五分").unwrap());
+    assert_eq!(Buai::from_percent(buai.to_percent()), Ok(buai));
+}
+
+#[test]
+fn check_buai_3() {
+    let percent = Kansuji::from(100000_u128);
+    assert_eq!(Buai::from_percent(percent), Err(KansujiError::TooLarge));
+    assert_eq!(Buai::try_from(2000.0), Err(KansujiError::OutOfRange));
+    assert_eq!(Buai::try_from(-0.1), Err(KansujiError::OutOfRange));
+    assert_eq!(Buai::try_from(f64::NAN), Err(KansujiError::OutOfRange));
+    assert_eq!(Buai::try_from(999.9999), Ok(Buai::from_mou(9999999)));
+}
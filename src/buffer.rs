@@ -0,0 +1,137 @@
+//! 文字列を確保せずに漢数字へ変換するための固定長の書き込み先
+//!
+//! ```
+//! use kansuji::{Kansuji, KansujiBuffer, KansujiFormat};
+//!
+//! let mut buffer = KansujiBuffer::new();
+//! let format = KansujiFormat::default();
+//! assert_eq!(buffer.format(Kansuji::from(320_u16), &format), "三百二十");
+//! assert_eq!(buffer.format(Kansuji::from(1.5), &format), "一五分");
+//! ```
+
+use super::{Kansuji, KansujiFormat};
+use core::fmt;
+
+/// 漢数字を書き込むためのスタック上の領域
+///
+/// どの書式でも最長の漢数字（`九千九百九十九垓…九毛`の53文字）が収まる大きさを持つ。
+#[derive(Clone, Copy)]
+pub struct KansujiBuffer {
+    bytes: [u8; KansujiBuffer::CAPACITY],
+    len: usize,
+}
+
+impl KansujiBuffer {
+    /// 書き込める最大のバイト数
+    pub const CAPACITY: usize = 53 * 3;
+
+    /// 空の領域を作る
+    pub fn new() -> Self {
+        KansujiBuffer {
+            bytes: [0; KansujiBuffer::CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// 書式を指定して漢数字を書き込み、その文字列を返す
+    ///
+    /// 前に書き込んだ内容は消える。
+    pub fn format(&mut self, kansuji: Kansuji, format: &KansujiFormat) -> &str {
+        self.len = 0;
+        // 最長の漢数字も収まるため失敗しない
+        let _ = kansuji.write_to(self, format);
+        self.as_str()
+    }
+
+    /// 書き込んだ文字列を返す
+    pub fn as_str(&self) -> &str {
+        // write_strで文字列ごとに書き込むため、常にUTF-8として正しい
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
+
+impl Default for KansujiBuffer {
+    fn default() -> Self {
+        KansujiBuffer::new()
+    }
+}
+
+impl fmt::Debug for KansujiBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("KansujiBuffer")
+            .field(&self.as_str())
+            .finish()
+    }
+}
+
+impl fmt::Write for KansujiBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > KansujiBuffer::CAPACITY {
+            return Err(fmt::Error);
+        }
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[test]
+fn check_kansuji_buffer_1() {
+    use super::Kurai;
+
+    let max = Kansuji::builder()
+        .set(Kurai::垓, 9999)
+        .set(Kurai::京, 9999)
+        .set(Kurai::兆, 9999)
+        .set(Kurai::億, 9999)
+        .set(Kurai::万, 9999)
+        .set(Kurai::一, 9999)
+        .set(Kurai::分, 9)
+        .set(Kurai::厘, 9)
+        .set(Kurai::毛, 9)
+        .build()
+        .unwrap();
+    let mut buffer = KansujiBuffer::new();
+    for format in [
+        KansujiFormat::default(),
+        KansujiFormat {
+            daiji: true,
+            ..KansujiFormat::default()
+        },
+        KansujiFormat {
+            explicit_one: true,
+            ..KansujiFormat::default()
+        },
+    ]
+    .iter()
+    {
+        let s = buffer.format(max, format);
+        assert_eq!(s.len(), KansujiBuffer::CAPACITY);
+        #[cfg(feature = "alloc")]
+        assert_eq!(s, max.to_string_with(format));
+    }
+    assert_eq!(
+        buffer.format(Kansuji::default(), &KansujiFormat::default()),
+        "零"
+    );
+    assert_eq!(buffer.as_str(), "零");
+}
+
+#[test]
+fn check_kansuji_buffer_2() {
+    let format = KansujiFormat::default();
+    let mut buffer = KansujiBuffer::new();
+    for n in [0_u128, 1, 10, 11, 200, 210501, 76492334, 1999999].iter() {
+        let kansuji = Kansuji::from(*n);
+        let s = buffer.format(kansuji, &format);
+        assert_eq!(Kansuji::try_from(s), Ok(kansuji));
+    }
+    let kansuji = Kansuji::try_from("参萬伍阡壱五分").unwrap();
+    assert_eq!(buffer.format(kansuji, &format), "三万五千一五分");
+    let daiji = KansujiFormat {
+        daiji: true,
+        ..KansujiFormat::default()
+    };
+    assert_eq!(buffer.format(kansuji, &daiji), "参萬伍阡壱伍分");
+}
@@ -0,0 +1,258 @@
+//! clapでコマンドライン引数を読むためのパーサー（`clap` featureで有効）
+//!
+//! [`Kansuji`]は`value_parser!(Kansuji)`で、漢数字と十進の文字列（`320`・`1.5`）のどちらからも読む。
+//! 整数型の引数は[`IntegerValueParser`]で読む。
+//!
+//! 読めない場合は、問題のある文字の位置を示したエラーを返す。
+//!
+//! ```text
+//! error: invalid value '三千円' for '--limit <limit>': unexpected char: 円
+//!
+//!     三千円
+//!         ^^
+//! ```
+//!
+//! ```
+//! use clap::{value_parser, Arg, Command};
+//! use kansuji::clap::IntegerValueParser;
+//! use kansuji::Kansuji;
+//!
+//! let cmd = Command::new("app")
+//!     .arg(Arg::new("limit").long("limit").value_parser(IntegerValueParser::<u64>::new()))
+//!     .arg(Arg::new("rate").long("rate").value_parser(value_parser!(Kansuji)));
+//! let matches = cmd
+//!     .try_get_matches_from(["app", "--limit", "三千", "--rate", "1.5"])
+//!     .unwrap();
+//! assert_eq!(matches.get_one::<u64>("limit"), Some(&3000));
+//! assert_eq!(matches.get_one::<Kansuji>("rate"), Some(&Kansuji::from(1.5)));
+//! ```
+
+use super::{parse_kansuji_or_decimal_at, Kansuji, KansujiError};
+use ::clap::builder::{TypedValueParser, ValueParserFactory};
+use ::clap::error::{Error, ErrorKind};
+use ::clap::{Arg, Command};
+use std::convert::TryFrom;
+use std::ffi::OsStr;
+use std::marker::PhantomData;
+
+/// [`Kansuji`]を読むパーサー
+///
+/// 漢数字と十進の文字列のどちらも受け付ける。
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct KansujiValueParser;
+
+impl KansujiValueParser {
+    /// パーサーを作る（`value_parser!(Kansuji)`と同じ）
+    pub fn new() -> Self {
+        KansujiValueParser
+    }
+}
+
+impl TypedValueParser for KansujiValueParser {
+    type Value = Kansuji;
+    fn parse_ref(&self, cmd: &Command, arg: Option<&Arg>, value: &OsStr) -> Result<Kansuji, Error> {
+        let s = value_to_str(cmd, value)?;
+        parse_kansuji_or_decimal_at(s).map_err(|e| kansuji_error(cmd, arg, s, e))
+    }
+}
+
+impl ValueParserFactory for Kansuji {
+    type Parser = KansujiValueParser;
+    fn value_parser() -> Self::Parser {
+        KansujiValueParser
+    }
+}
+
+/// 整数型の値を漢数字と算用数字のどちらからも読むパーサー
+///
+/// 小数部がある場合や、型に収まらない場合はエラーとする。
+///
+/// ```
+/// use clap::{Arg, Command};
+/// use kansuji::clap::IntegerValueParser;
+///
+/// let cmd = Command::new("app").arg(Arg::new("count").value_parser(IntegerValueParser::<u8>::new()));
+/// assert!(cmd.clone().try_get_matches_from(["app", "二百"]).is_ok());
+/// assert!(cmd.try_get_matches_from(["app", "三百"]).is_err());
+/// ```
+pub struct IntegerValueParser<T> {
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> IntegerValueParser<T> {
+    /// `T`を読むパーサーを作る
+    pub fn new() -> Self {
+        IntegerValueParser {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for IntegerValueParser<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for IntegerValueParser<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for IntegerValueParser<T> {}
+
+impl<T> std::fmt::Debug for IntegerValueParser<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "IntegerValueParser<{}>", std::any::type_name::<T>())
+    }
+}
+
+impl<T> TypedValueParser for IntegerValueParser<T>
+where
+    T: TryFrom<u128> + Clone + Send + Sync + 'static,
+{
+    type Value = T;
+    fn parse_ref(&self, cmd: &Command, arg: Option<&Arg>, value: &OsStr) -> Result<T, Error> {
+        let s = value_to_str(cmd, value)?;
+        let kansuji = parse_kansuji_or_decimal_at(s).map_err(|e| kansuji_error(cmd, arg, s, e))?;
+        if kansuji.has_fraction() {
+            return Err(value_error(cmd, arg, s, "not an integer", None));
+        }
+        T::try_from(kansuji.to_u128()).map_err(|_| {
+            let reason = format!("too large for {}", std::any::type_name::<T>());
+            value_error(cmd, arg, s, &reason, None)
+        })
+    }
+}
+
+fn value_to_str<'a>(cmd: &Command, value: &'a OsStr) -> Result<&'a str, Error> {
+    value.to_str().ok_or_else(|| {
+        cmd.clone()
+            .error(ErrorKind::InvalidUtf8, "invalid UTF-8 was detected")
+    })
+}
+
+/// 読めなかった文字や位置を示したエラーを作る
+fn kansuji_error(
+    cmd: &Command,
+    arg: Option<&Arg>,
+    value: &str,
+    (e, position): (KansujiError, usize),
+) -> Error {
+    let position = match e {
+        KansujiError::UnexpectedChar(_) => Some(position),
+        KansujiError::UnexpectedEnd if !value.is_empty() => Some(position),
+        _ => None,
+    };
+    value_error(cmd, arg, value, &e.to_string(), position)
+}
+
+/// 端末での表示幅（全角文字は二桁分として数える）
+fn display_width(c: char) -> usize {
+    if c.is_ascii() {
+        1
+    } else {
+        2
+    }
+}
+
+fn value_error(
+    cmd: &Command,
+    arg: Option<&Arg>,
+    value: &str,
+    reason: &str,
+    position: Option<usize>,
+) -> Error {
+    let arg = arg.map_or_else(|| "...".to_string(), |arg| arg.to_string());
+    let mut message = format!("invalid value '{}' for '{}': {}", value, arg, reason);
+    if let Some(position) = position {
+        let column: usize = value[..position].chars().map(display_width).sum();
+        let width = value[position..].chars().next().map_or(1, display_width);
+        message.push_str(&format!(
+            "\n\n    {}\n    {}{}",
+            value,
+            " ".repeat(column),
+            "^".repeat(width)
+        ));
+    }
+    cmd.clone().error(ErrorKind::ValueValidation, message)
+}
+
+#[cfg(test)]
+fn test_command() -> Command {
+    Command::new("app")
+        .arg(
+            Arg::new("limit")
+                .long("limit")
+                .value_parser(IntegerValueParser::<u8>::new()),
+        )
+        .arg(
+            Arg::new("rate")
+                .long("rate")
+                .value_parser(::clap::value_parser!(Kansuji)),
+        )
+}
+
+#[test]
+fn check_clap_1() {
+    let matches = test_command()
+        .try_get_matches_from(["app", "--limit", "二百", "--rate", "一二分五厘"])
+        .unwrap();
+    assert_eq!(matches.get_one::<u8>("limit"), Some(&200));
+    assert_eq!(
+        matches.get_one::<Kansuji>("rate"),
+        Some(&Kansuji::from(1.25))
+    );
+    let matches = test_command()
+        .try_get_matches_from(["app", "--limit", "200", "--rate", "1.25"])
+        .unwrap();
+    assert_eq!(matches.get_one::<u8>("limit"), Some(&200));
+    assert_eq!(
+        matches.get_one::<Kansuji>("rate"),
+        Some(&Kansuji::from(1.25))
+    );
+}
+
+#[test]
+fn check_clap_2() {
+    let error = |args: &[&str]| {
+        let e = test_command()
+            .try_get_matches_from(["app"].iter().chain(args))
+            .unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::ValueValidation);
+        e.to_string()
+    };
+    assert_eq!(
+        error(&["--limit", "三千円"]),
+        "error: invalid value '三千円' for '--limit <limit>': unexpected char: 円\n\n    三千円\n        ^^\n"
+    );
+    assert_eq!(
+        error(&["--limit", "a3"]),
+        "error: invalid value 'a3' for '--limit <limit>': unexpected char: a\n\n    a3\n    ^\n"
+    );
+    assert_eq!(
+        error(&["--limit", "二万三万"]),
+        "error: invalid value '二万三万' for '--limit <limit>': unexpected char: 万\n\n    二万三万\n          ^^\n"
+    );
+    assert_eq!(
+        error(&["--rate", "十十"]),
+        "error: invalid value '十十' for '--rate <rate>': unexpected char: 十\n\n    十十\n      ^^\n"
+    );
+    assert_eq!(
+        error(&["--limit", "一二"]),
+        "error: invalid value '一二' for '--limit <limit>': unexpected char: 二\n\n    一二\n      ^^\n"
+    );
+    assert_eq!(
+        error(&["--rate", "1.2.3"]),
+        "error: invalid value '1.2.3' for '--rate <rate>': unexpected char: .\n\n    1.2.3\n       ^\n"
+    );
+    assert_eq!(
+        error(&["--limit", "三百"]),
+        "error: invalid value '三百' for '--limit <limit>': too large for u8\n"
+    );
+    assert_eq!(
+        error(&["--limit", "1.5"]),
+        "error: invalid value '1.5' for '--limit <limit>': not an integer\n"
+    );
+}
@@ -0,0 +1,407 @@
+//! 元の表記を失わない漢数字の構文木（CST）
+//!
+//! [`Kansuji`]は読んだ時点で値だけを持つため、`一千`と`千`や`万`と`萬`の違いは失われる。
+//! [`KansujiCst`]はすべての数字と位を位置付きのトークンとして持ち、元の文字列をそのまま再現できる。
+//! また、[`KansujiFormat`]で指定した書式に合わないトークンだけを書き換えることができる。
+//!
+//! ```
+//! use kansuji::{KansujiCst, KansujiFormat};
+//!
+//! let cst = KansujiCst::try_from("一千弐百萬").unwrap();
+//! assert_eq!(cst.to_source(), "一千弐百萬");
+//! assert_eq!(u128::from(cst.kansuji()), 12_000_000);
+//!
+//! let format = KansujiFormat::default();
+//! assert_eq!(cst.normalize(&format), "千二百万");
+//! ```
+
+use super::{
+    arabic_digit, Kansuji, KansujiError, KansujiField, KansujiFormat, KansujiKeta, TokenKind,
+};
+use alloc::string::{String, ToString};
+#[cfg(test)]
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::Range;
+
+/// 元の文字列での位置を持つトークン
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct CstToken {
+    kind: TokenKind,
+    text: String,
+    span: Range<usize>,
+}
+
+impl CstToken {
+    /// トークンの種類を返す
+    pub fn kind(&self) -> TokenKind {
+        self.kind
+    }
+
+    /// 元の表記を返す
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// 元の文字列でのバイト位置を返す
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    fn is_arabic(&self) -> bool {
+        self.text.chars().all(|c| arabic_digit(c).is_some())
+    }
+}
+
+/// 十・百・千の位一つ分（`三百`・`千`・`五`・`35`など）
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct CstTerm {
+    digits: Vec<CstToken>,
+    unit: Option<CstToken>,
+}
+
+impl CstTerm {
+    /// 数字のトークンを返す
+    ///
+    /// 算用数字が続く場合（`35`など）は複数になり、`千`のように数字を省略した場合は空になる。
+    pub fn digits(&self) -> &[CstToken] {
+        &self.digits
+    }
+
+    /// 十・百・千のトークンを返す
+    pub fn unit(&self) -> Option<&CstToken> {
+        self.unit.as_ref()
+    }
+}
+
+/// 万・億などや分・厘・毛で区切られた位のまとまり
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct CstGroup {
+    terms: Vec<CstTerm>,
+    unit: Option<CstToken>,
+}
+
+impl CstGroup {
+    /// 十・百・千の位を上から順に返す
+    pub fn terms(&self) -> &[CstTerm] {
+        &self.terms
+    }
+
+    /// まとまりの後ろの万・億・分などのトークンを返す
+    pub fn unit(&self) -> Option<&CstToken> {
+        self.unit.as_ref()
+    }
+}
+
+/// 元の表記を失わない漢数字の構文木
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KansujiCst {
+    まとまり: Vec<CstGroup>,
+    数: Kansuji,
+}
+
+impl KansujiCst {
+    /// 位のまとまりを上から順に返す
+    pub fn groups(&self) -> &[CstGroup] {
+        &self.まとまり
+    }
+
+    /// すべてのトークンを先頭から順に返す
+    pub fn tokens(&self) -> Vec<&CstToken> {
+        let mut tokens = Vec::new();
+        for group in self.まとまり.iter() {
+            for term in group.terms.iter() {
+                tokens.extend(term.digits.iter());
+                tokens.extend(term.unit.iter());
+            }
+            tokens.extend(group.unit.iter());
+        }
+        tokens
+    }
+
+    /// 値を返す
+    pub fn kansuji(&self) -> Kansuji {
+        self.数
+    }
+
+    /// 元の文字列をそのまま返す
+    pub fn to_source(&self) -> String {
+        self.tokens().iter().map(|t| t.text.as_str()).collect()
+    }
+
+    /// 書式に合わないトークンの位置と、置き換える文字列を返す
+    ///
+    /// 位置が空の範囲の場合は、その位置に文字列を挿入することを表す。
+    pub fn edits(&self, format: &KansujiFormat) -> Vec<(Range<usize>, String)> {
+        let mut edits = Vec::new();
+        let digit_text = |d: u8| {
+            let field = KansujiField::from_int(d);
+            if format.daiji {
+                field.to_daiji()
+            } else {
+                field.to_str2()
+            }
+        };
+        for group in self.まとまり.iter() {
+            for term in group.terms.iter() {
+                if term.digits.len() > 1 {
+                    edits.extend(arabic_edit(term, format));
+                    continue;
+                }
+                let unit = term.unit.as_ref().map(|t| match t.kind {
+                    TokenKind::SmallUnit(n) => n,
+                    _ => unreachable!(),
+                });
+                // 一千・壱拾のように位の前の一を書くかどうか
+                let want_one = format.daiji || (format.explicit_one && unit == Some(1000));
+                match (term.digits.first(), unit) {
+                    (Some(t), _) if t.kind == TokenKind::Digit(0) => (),
+                    (Some(t), Some(_)) if t.kind == TokenKind::Digit(1) && !want_one => {
+                        edits.push((t.span(), String::new()));
+                    }
+                    (Some(t), _) => {
+                        if let TokenKind::Digit(d) = t.kind {
+                            let text = digit_text(d);
+                            if t.text != text {
+                                edits.push((t.span(), text.to_string()));
+                            }
+                        }
+                    }
+                    (None, Some(_)) if want_one => {
+                        if let Some(t) = &term.unit {
+                            edits.push((t.span.start..t.span.start, digit_text(1).to_string()));
+                        }
+                    }
+                    (None, _) => (),
+                }
+                if let (Some(t), Some(n)) = (&term.unit, unit) {
+                    let text = match (n, format.daiji) {
+                        (10, false) => "十",
+                        (10, true) => "拾",
+                        (100, false) => "百",
+                        (100, true) => "佰",
+                        (_, false) => "千",
+                        (_, true) => "阡",
+                    };
+                    if t.text != text {
+                        edits.push((t.span(), text.to_string()));
+                    }
+                }
+            }
+            if let Some(t) = &group.unit {
+                if t.kind == TokenKind::MyriadUnit(1) {
+                    let text = if format.daiji { "萬" } else { "万" };
+                    if t.text != text {
+                        edits.push((t.span(), text.to_string()));
+                    }
+                }
+            }
+        }
+        edits
+    }
+
+    /// 書式に合わないトークンだけを書き換えた文字列を返す
+    pub fn normalize(&self, format: &KansujiFormat) -> String {
+        let mut s = self.to_source();
+        for (span, text) in self.edits(format).iter().rev() {
+            s.replace_range(span.clone(), text);
+        }
+        s
+    }
+}
+
+/// `35`のように算用数字が続く位を漢数字に書き換える
+fn arabic_edit(term: &CstTerm, format: &KansujiFormat) -> Option<(Range<usize>, String)> {
+    let first = term.digits.first()?;
+    let last = term.digits.last()?;
+    let n = term.digits.iter().fold(0, |n, t| match t.kind {
+        TokenKind::Digit(d) => n * 10 + d as usize,
+        _ => n,
+    });
+    if n == 0 {
+        return None;
+    }
+    let mut text = String::new();
+    // Stringへの書き込みは失敗しない
+    let _ = KansujiKeta::from(n).write_with(&mut text, format);
+    Some((first.span.start..last.span.end, text))
+}
+
+impl TryFrom<String> for KansujiCst {
+    type Error = KansujiError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_cst(&value)
+    }
+}
+
+impl TryFrom<&String> for KansujiCst {
+    type Error = KansujiError;
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        parse_cst(value)
+    }
+}
+
+impl TryFrom<&str> for KansujiCst {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_cst(value)
+    }
+}
+
+fn parse_cst(s: &str) -> Result<KansujiCst, KansujiError> {
+    let kansuji = Kansuji::try_from(s)?;
+    let mut groups = Vec::new();
+    let mut terms = Vec::new();
+    let mut digits: Vec<CstToken> = Vec::new();
+    for (i, c) in s.char_indices() {
+        let token = CstToken {
+            kind: TokenKind::classify(c),
+            text: c.to_string(),
+            span: i..i + c.len_utf8(),
+        };
+        match token.kind {
+            TokenKind::Digit(_) => {
+                // 一五分のように漢数字が続く場合は、前の数字で位のまとまりが終わる
+                if digits
+                    .last()
+                    .map_or(false, |t| !(t.is_arabic() && token.is_arabic()))
+                {
+                    terms.push(CstTerm {
+                        digits: core::mem::take(&mut digits),
+                        unit: None,
+                    });
+                    groups.push(CstGroup {
+                        terms: core::mem::take(&mut terms),
+                        unit: None,
+                    });
+                }
+                digits.push(token);
+            }
+            TokenKind::SmallUnit(_) => terms.push(CstTerm {
+                digits: core::mem::take(&mut digits),
+                unit: Some(token),
+            }),
+            TokenKind::MyriadUnit(_) | TokenKind::FractionUnit(_) => {
+                if !digits.is_empty() {
+                    terms.push(CstTerm {
+                        digits: core::mem::take(&mut digits),
+                        unit: None,
+                    });
+                }
+                groups.push(CstGroup {
+                    terms: core::mem::take(&mut terms),
+                    unit: Some(token),
+                });
+            }
+            TokenKind::Separator(_) | TokenKind::Sign(_) | TokenKind::Other(_) => {
+                return Err(KansujiError::UnexpectedChar(c))
+            }
+        }
+    }
+    if !digits.is_empty() {
+        terms.push(CstTerm { digits, unit: None });
+    }
+    if !terms.is_empty() {
+        groups.push(CstGroup { terms, unit: None });
+    }
+    Ok(KansujiCst {
+        まとまり: groups,
+        数: kansuji,
+    })
+}
+
+#[test]
+fn check_parse_cst_1() {
+    fn cst_test_function(s: &&str) {
+        let cst = parse_cst(s).unwrap();
+        assert_eq!(cst.to_source(), *s);
+        assert_eq!(cst.kansuji(), Kansuji::try_from(*s).unwrap());
+        for token in cst.tokens() {
+            assert_eq!(&s[token.span()], token.text());
+        }
+    }
+
+    let v = [
+        "千",
+        "一千",
+        "壱阡弐佰参拾",
+        "三万五千",
+        "三萬五千",
+        "3万5千",
+        "35万",
+        "百二十三兆五百四十万二",
+        "一五分",
+        "零",
+    ];
+    v.iter().for_each(cst_test_function);
+}
+
+#[test]
+fn check_parse_cst_2() {
+    let cst = parse_cst("一千弐百萬").unwrap();
+    assert_eq!(cst.groups().len(), 1);
+    let group = &cst.groups()[0];
+    assert_eq!(
+        group.unit().map(|t| t.kind()),
+        Some(TokenKind::MyriadUnit(1))
+    );
+    assert_eq!(group.unit().map(|t| t.span()), Some(12..15));
+    assert_eq!(group.terms().len(), 2);
+    assert_eq!(group.terms()[0].digits()[0].text(), "一");
+    assert_eq!(group.terms()[1].digits()[0].kind(), TokenKind::Digit(2));
+    assert_eq!(parse_cst("三個"), Err(KansujiError::UnexpectedChar('個')));
+}
+
+#[test]
+fn check_cst_normalize_1() {
+    let format = KansujiFormat::default();
+    let v = [
+        ("一千", "千"),
+        ("千", "千"),
+        ("壱阡弐佰参拾", "千二百三十"),
+        ("三萬五千", "三万五千"),
+        ("3万5千", "三万五千"),
+        ("35万", "三十五万"),
+        ("一百一十一", "百十一"),
+        ("1千", "千"),
+    ];
+    for (s, t) in v.iter() {
+        assert_eq!(parse_cst(s).unwrap().normalize(&format), *t);
+    }
+}
+
+#[test]
+fn check_cst_normalize_2() {
+    let format = KansujiFormat {
+        explicit_one: true,
+        ..KansujiFormat::default()
+    };
+    assert_eq!(parse_cst("千百").unwrap().normalize(&format), "一千百");
+    assert_eq!(parse_cst("一千").unwrap().normalize(&format), "一千");
+    let format = KansujiFormat {
+        daiji: true,
+        ..KansujiFormat::default()
+    };
+    assert_eq!(
+        parse_cst("千二百三十万").unwrap().normalize(&format),
+        "壱阡弐佰参拾萬"
+    );
+    assert_eq!(parse_cst("12万").unwrap().normalize(&format), "壱拾弐萬");
+    // 書式に合っているトークンは書き換えない
+    let cst = parse_cst("三万五千").unwrap();
+    assert_eq!(cst.edits(&KansujiFormat::default()), Vec::new());
+    assert_eq!(
+        parse_cst("一千万")
+            .unwrap()
+            .edits(&KansujiFormat::default()),
+        vec![(0..3, String::new())]
+    );
+}
+
+impl fmt::Display for KansujiCst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_source())
+    }
+}
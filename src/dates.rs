@@ -0,0 +1,488 @@
+//! 和暦と西暦による日付の解析と変換を行う
+//!
+//! 元号は明治から令和までに対応し、改元の日を境に西暦（先発グレゴリオ暦）と相互に変換する。
+//! 年・月・日の数は[`Kansuji`]として読むほか、`二〇二四年`のような位取り記数法も読むことができる。
+//!
+//! ```
+//! use kansuji::{Gengo, Hizuke};
+//!
+//! let hizuke = Hizuke::try_from("令和六年十月十六日").unwrap();
+//! assert_eq!(hizuke.gengo(), Some(Gengo::令和));
+//! assert_eq!(hizuke.to_seireki().to_string(), "二〇二四年十月十六日");
+//!
+//! let hizuke = Hizuke::try_from("1989年1月8日").unwrap();
+//! assert_eq!(hizuke.to_wareki().unwrap().to_string(), "平成元年一月八日");
+//! ```
+
+use super::{parse_integer, Kansuji, KansujiError};
+use alloc::string::{String, ToString};
+use core::convert::{From, TryFrom};
+use core::fmt;
+
+/// 元号
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
+pub enum Gengo {
+    明治,
+    大正,
+    昭和,
+    平成,
+    令和,
+}
+
+impl Gengo {
+    const ALL: [Gengo; 5] = [
+        Gengo::明治,
+        Gengo::大正,
+        Gengo::昭和,
+        Gengo::平成,
+        Gengo::令和,
+    ];
+
+    /// 元号の名前を返す
+    pub fn name(self) -> &'static str {
+        match self {
+            Gengo::明治 => "明治",
+            Gengo::大正 => "大正",
+            Gengo::昭和 => "昭和",
+            Gengo::平成 => "平成",
+            Gengo::令和 => "令和",
+        }
+    }
+
+    /// 合字で書かれた元号の名前を返す
+    fn ligature(self) -> char {
+        match self {
+            Gengo::明治 => '㍾',
+            Gengo::大正 => '㍽',
+            Gengo::昭和 => '㍼',
+            Gengo::平成 => '㍻',
+            Gengo::令和 => '㋿',
+        }
+    }
+
+    /// 改元の日を西暦の`(年, 月, 日)`で返す
+    pub fn start(self) -> (u32, u8, u8) {
+        match self {
+            Gengo::明治 => (1868, 10, 23),
+            Gengo::大正 => (1912, 7, 30),
+            Gengo::昭和 => (1926, 12, 25),
+            Gengo::平成 => (1989, 1, 8),
+            Gengo::令和 => (2019, 5, 1),
+        }
+    }
+
+    fn next(self) -> Option<Gengo> {
+        match self {
+            Gengo::明治 => Some(Gengo::大正),
+            Gengo::大正 => Some(Gengo::昭和),
+            Gengo::昭和 => Some(Gengo::平成),
+            Gengo::平成 => Some(Gengo::令和),
+            Gengo::令和 => None,
+        }
+    }
+}
+
+impl fmt::Display for Gengo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl TryFrom<&str> for Gengo {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match strip_gengo(value) {
+            (Some(gengo), "") => Ok(gengo),
+            (Some(_), rest) | (None, rest) => Err(rest
+                .chars()
+                .next()
+                .map(KansujiError::UnexpectedChar)
+                .unwrap_or(KansujiError::UnexpectedEnd)),
+        }
+    }
+}
+
+fn strip_gengo(s: &str) -> (Option<Gengo>, &str) {
+    for gengo in Gengo::ALL {
+        if let Some(rest) = s.strip_prefix(gengo.name()) {
+            return (Some(gengo), rest);
+        }
+        if let Some(rest) = s.strip_prefix(gengo.ligature()) {
+            return (Some(gengo), rest);
+        }
+    }
+    (None, s)
+}
+
+/// 日付
+///
+/// 元号がない場合は西暦を表す。月と日は省略することができる。
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct Hizuke {
+    元号: Option<Gengo>,
+    年: u32,
+    月: Option<u8>,
+    日: Option<u8>,
+}
+
+impl Hizuke {
+    /// 西暦の日付を作る
+    pub fn seireki(year: u32, month: Option<u8>, day: Option<u8>) -> Result<Self, KansujiError> {
+        check_month_day(year, month, day)?;
+        Ok(Hizuke {
+            元号: None,
+            年: year,
+            月: month,
+            日: day,
+        })
+    }
+
+    /// 和暦の日付を作る
+    ///
+    /// 元号の期間外の日付はエラーとなる。
+    pub fn wareki(
+        gengo: Gengo,
+        year: u32,
+        month: Option<u8>,
+        day: Option<u8>,
+    ) -> Result<Self, KansujiError> {
+        if year == 0 {
+            return Err(KansujiError::OutOfRange);
+        }
+        let seireki = seireki_year(gengo, year).ok_or(KansujiError::TooLarge)?;
+        check_month_day(seireki, month, day)?;
+        let first = (seireki, month.unwrap_or(1), day.unwrap_or(1));
+        let last = period_end(seireki, month, day);
+        if last < gengo.start() || gengo.next().map_or(false, |next| next.start() <= first) {
+            return Err(KansujiError::OutOfRange);
+        }
+        Ok(Hizuke {
+            元号: Some(gengo),
+            年: year,
+            月: month,
+            日: day,
+        })
+    }
+
+    /// 元号を返す
+    pub fn gengo(&self) -> Option<Gengo> {
+        self.元号
+    }
+
+    /// 年を返す
+    pub fn year(&self) -> u32 {
+        self.年
+    }
+
+    /// 月を返す
+    pub fn month(&self) -> Option<u8> {
+        self.月
+    }
+
+    /// 日を返す
+    pub fn day(&self) -> Option<u8> {
+        self.日
+    }
+
+    /// 西暦に変換する
+    pub fn to_seireki(&self) -> Hizuke {
+        match self.元号 {
+            Some(gengo) => Hizuke {
+                元号: None,
+                // wareki()で桁あふれしないことを確かめている
+                年: seireki_year(gengo, self.年).unwrap_or(u32::MAX),
+                ..*self
+            },
+            None => *self,
+        }
+    }
+
+    /// 和暦に変換する
+    ///
+    /// 月や日が省略されていて期間の途中で改元がある場合は、期間の終わりの元号を使う。
+    /// 明治より前の日付はエラーとなる。
+    pub fn to_wareki(&self) -> Result<Hizuke, KansujiError> {
+        if self.元号.is_some() {
+            return Ok(*self);
+        }
+        let last = period_end(self.年, self.月, self.日);
+        let gengo = Gengo::ALL
+            .iter()
+            .rev()
+            .find(|gengo| gengo.start() <= last)
+            .ok_or(KansujiError::OutOfRange)?;
+        Ok(Hizuke {
+            元号: Some(*gengo),
+            年: self.年 + 1 - gengo.start().0,
+            ..*self
+        })
+    }
+}
+
+/// 和暦の年を西暦の年にする
+fn seireki_year(gengo: Gengo, year: u32) -> Option<u32> {
+    (gengo.start().0 - 1).checked_add(year)
+}
+
+fn is_leap_year(year: u32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: u32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn check_month_day(year: u32, month: Option<u8>, day: Option<u8>) -> Result<(), KansujiError> {
+    match (month, day) {
+        (None, None) => Ok(()),
+        (None, Some(_)) => Err(KansujiError::ParseError),
+        (Some(month), _) if !(1..=12).contains(&month) => Err(KansujiError::OutOfRange),
+        (Some(_), None) => Ok(()),
+        (Some(month), Some(day)) => {
+            if 1 <= day && day <= days_in_month(year, month) {
+                Ok(())
+            } else {
+                Err(KansujiError::OutOfRange)
+            }
+        }
+    }
+}
+
+/// 省略された月や日を補い、期間の最後の日を返す
+fn period_end(year: u32, month: Option<u8>, day: Option<u8>) -> (u32, u8, u8) {
+    let month = month.unwrap_or(12);
+    (
+        year,
+        month,
+        day.unwrap_or_else(|| days_in_month(year, month)),
+    )
+}
+
+impl TryFrom<String> for Hizuke {
+    type Error = KansujiError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_hizuke(&value)
+    }
+}
+
+impl TryFrom<&String> for Hizuke {
+    type Error = KansujiError;
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        parse_hizuke(value)
+    }
+}
+
+impl TryFrom<&str> for Hizuke {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_hizuke(value)
+    }
+}
+
+fn parse_hizuke(s: &str) -> Result<Hizuke, KansujiError> {
+    let (gengo, s) = strip_gengo(s);
+    let s = if gengo.is_none() {
+        s.strip_prefix("西暦").unwrap_or(s)
+    } else {
+        s
+    };
+    let (year, rest) = s.split_once('年').ok_or(KansujiError::UnexpectedEnd)?;
+    let year = match (gengo, year) {
+        (Some(_), "元") => 1,
+        _ => parse_field(year)?,
+    };
+    let (month, day) = if rest.is_empty() {
+        (None, None)
+    } else {
+        let (month, rest) = split_field(rest, '月')?;
+        if rest.is_empty() {
+            (Some(month), None)
+        } else {
+            let (day, rest) = split_field(rest, '日')?;
+            if let Some(c) = rest.chars().next() {
+                return Err(KansujiError::UnexpectedChar(c));
+            }
+            (Some(month), Some(day))
+        }
+    };
+    let month = month
+        .map(u8::try_from)
+        .transpose()
+        .map_err(|_| KansujiError::OutOfRange)?;
+    let day = day
+        .map(u8::try_from)
+        .transpose()
+        .map_err(|_| KansujiError::OutOfRange)?;
+    match gengo {
+        Some(gengo) => Hizuke::wareki(gengo, year, month, day),
+        None => Hizuke::seireki(year, month, day),
+    }
+}
+
+fn parse_field(s: &str) -> Result<u32, KansujiError> {
+    u32::try_from(parse_integer(s)?).map_err(|_| KansujiError::TooLarge)
+}
+
+fn split_field(s: &str, unit: char) -> Result<(u32, &str), KansujiError> {
+    match s.split_once(unit) {
+        Some((n, rest)) => Ok((parse_field(n)?, rest)),
+        None => {
+            // 単位がない場合は、数として読めない最初の文字を報告する
+            parse_field(s)?;
+            Err(KansujiError::UnexpectedEnd)
+        }
+    }
+}
+
+#[test]
+fn check_parse_hizuke_1() {
+    assert_eq!(
+        parse_hizuke("令和六年十月十六日"),
+        Ok(Hizuke {
+            元号: Some(Gengo::令和),
+            年: 6,
+            月: Some(10),
+            日: Some(16),
+        })
+    );
+    assert_eq!(
+        parse_hizuke("平成元年"),
+        Hizuke::wareki(Gengo::平成, 1, None, None)
+    );
+    assert_eq!(
+        parse_hizuke("㍼六十四年一月"),
+        Hizuke::wareki(Gengo::昭和, 64, Some(1), None)
+    );
+}
+
+#[test]
+fn check_parse_hizuke_2() {
+    assert_eq!(
+        parse_hizuke("二〇二四年十月十六日"),
+        Hizuke::seireki(2024, Some(10), Some(16))
+    );
+    assert_eq!(
+        parse_hizuke("西暦二千二十四年"),
+        Hizuke::seireki(2024, None, None)
+    );
+    assert_eq!(
+        parse_hizuke("2024年2月29日"),
+        Hizuke::seireki(2024, Some(2), Some(29))
+    );
+}
+
+#[test]
+fn check_parse_hizuke_3() {
+    assert_eq!(parse_hizuke("平成三十二年"), Err(KansujiError::OutOfRange));
+    assert_eq!(
+        parse_hizuke("昭和六十四年一月八日"),
+        Err(KansujiError::OutOfRange)
+    );
+    assert_eq!(parse_hizuke("令和元年四月"), Err(KansujiError::OutOfRange));
+    assert_eq!(
+        parse_hizuke("二〇二三年二月二十九日"),
+        Err(KansujiError::OutOfRange)
+    );
+    assert_eq!(parse_hizuke("十三月"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(
+        parse_hizuke("元年"),
+        Err(KansujiError::UnexpectedChar('元'))
+    );
+    assert_eq!(
+        parse_hizuke("令和六年十月十六日に"),
+        Err(KansujiError::UnexpectedChar('に'))
+    );
+}
+
+impl fmt::Display for Hizuke {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.元号 {
+            Some(gengo) if self.年 == 1 => write!(f, "{}元年", gengo)?,
+            Some(gengo) => write!(f, "{}{}年", gengo, Kansuji::from(self.年))?,
+            None => {
+                for c in self.年.to_string().chars() {
+                    let d = c as u8 - b'0';
+                    if d == 0 {
+                        write!(f, "〇")?;
+                    } else {
+                        write!(f, "{}", Kansuji::from(d))?;
+                    }
+                }
+                write!(f, "年")?;
+            }
+        }
+        if let Some(month) = self.月 {
+            write!(f, "{}月", Kansuji::from(month))?;
+        }
+        if let Some(day) = self.日 {
+            write!(f, "{}日", Kansuji::from(day))?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn check_hizuke_1() {
+    fn hizuke_test_function(s: &str) {
+        let hizuke = Hizuke::try_from(s).unwrap();
+        assert_eq!(s, hizuke.to_string());
+    }
+
+    let v = [
+        "令和六年十月十六日",
+        "平成元年",
+        "明治四十五年七月二十九日",
+        "二〇二四年十月",
+        "一八六八年",
+    ];
+    v.iter().for_each(|s| hizuke_test_function(s));
+}
+
+#[test]
+fn check_hizuke_2() {
+    let hizuke = Hizuke::seireki(2024, Some(10), Some(16)).unwrap();
+    assert_eq!(
+        hizuke.to_wareki(),
+        Hizuke::wareki(Gengo::令和, 6, Some(10), Some(16))
+    );
+    let hizuke = Hizuke::seireki(1989, Some(1), Some(7)).unwrap();
+    assert_eq!(
+        hizuke.to_wareki(),
+        Hizuke::wareki(Gengo::昭和, 64, Some(1), Some(7))
+    );
+    let hizuke = Hizuke::seireki(2019, None, None).unwrap();
+    assert_eq!(
+        hizuke.to_wareki(),
+        Hizuke::wareki(Gengo::令和, 1, None, None)
+    );
+    let hizuke = Hizuke::seireki(1868, Some(1), None).unwrap();
+    assert_eq!(hizuke.to_wareki(), Err(KansujiError::OutOfRange));
+}
+
+#[test]
+fn check_hizuke_3() {
+    let hizuke = Hizuke::wareki(Gengo::大正, 15, Some(12), Some(24)).unwrap();
+    assert_eq!(
+        hizuke.to_seireki(),
+        Hizuke::seireki(1926, Some(12), Some(24)).unwrap()
+    );
+    assert_eq!(hizuke.to_seireki().to_wareki(), Ok(hizuke));
+    assert_eq!(
+        Hizuke::try_from("令和4294967295年"),
+        Err(KansujiError::TooLarge)
+    );
+    assert_eq!(
+        Hizuke::wareki(Gengo::令和, u32::MAX - 2018, None, None).map(|h| h.to_seireki()),
+        Hizuke::seireki(u32::MAX, None, None)
+    );
+    assert_eq!(Gengo::try_from("平成"), Ok(Gengo::平成));
+    assert_eq!(
+        Gengo::try_from("平"),
+        Err(KansujiError::UnexpectedChar('平'))
+    );
+}
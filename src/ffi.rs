@@ -0,0 +1,402 @@
+//! C言語から使うための関数（`ffi` featureで有効）
+//!
+//! ヘッダーファイルは`include/kansuji.h`にある。これは`cbindgen`で生成したものであり、
+//! 関数を変更した場合は次のようにして作り直す。
+//!
+//! ```sh
+//! cbindgen --config cbindgen.toml --output include/kansuji.h
+//! ```
+//!
+//! 文字列はUTF-8のポインタと長さで渡し、結果は呼び出し側が用意したバッファに終端の`NUL`付きで書き込む。
+//! 失敗した場合は[`KansujiStatus`]でその種類を返し、詳しいメッセージは[`kansuji_last_error_message`]で得られる。
+//!
+//! ```
+//! use kansuji::ffi::{kansuji_parse, KansujiStatus, KansujiValue};
+//!
+//! let s = "三百二十";
+//! let mut value = KansujiValue::default();
+//! let status = unsafe { kansuji_parse(s.as_ptr().cast(), s.len(), &mut value) };
+//! assert_eq!(status, KansujiStatus::Ok);
+//! assert_eq!(value.integer_low, 320);
+//! ```
+
+use super::{Kansuji, KansujiBuffer, KansujiError, KansujiFormat, KANSUJI_LIMIT};
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+
+/// 結果を書き込むバッファに必要な大きさ（終端の`NUL`を含む）
+///
+/// この大きさのバッファを渡せば、`KANSUJI_STATUS_BUFFER_TOO_SMALL`になることはない。
+pub const KANSUJI_BUFFER_CAPACITY: usize = 160;
+
+/// 関数の結果
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum KansujiStatus {
+    /// 成功した
+    Ok = 0,
+    /// 漢数字として読めない（UTF-8として正しくない場合を含む）
+    ParseError = 1,
+    /// 予期しない文字がある
+    UnexpectedChar = 2,
+    /// 予期しない位置で終わっている
+    UnexpectedEnd = 3,
+    /// 扱える範囲を超えている
+    TooLarge = 4,
+    /// 値が範囲外である
+    OutOfRange = 5,
+    /// 必要なポインタが`NULL`である
+    NullPointer = 6,
+    /// 結果を書き込むバッファが小さい
+    BufferTooSmall = 7,
+}
+
+impl From<KansujiError> for KansujiStatus {
+    fn from(e: KansujiError) -> Self {
+        match e {
+            KansujiError::ParseError => KansujiStatus::ParseError,
+            KansujiError::UnexpectedChar(_) => KansujiStatus::UnexpectedChar,
+            KansujiError::UnexpectedEnd => KansujiStatus::UnexpectedEnd,
+            KansujiError::TooLarge => KansujiStatus::TooLarge,
+            KansujiError::OutOfRange => KansujiStatus::OutOfRange,
+        }
+    }
+}
+
+/// 漢数字の値
+///
+/// Cには128ビットの整数型がないため、整数部を上位と下位の64ビットに分けて持つ。
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+pub struct KansujiValue {
+    /// 整数部の上位64ビット
+    pub integer_high: u64,
+    /// 整数部の下位64ビット
+    pub integer_low: u64,
+    /// 小数部（毛を単位とした0〜999）
+    pub fraction: u16,
+}
+
+impl From<Kansuji> for KansujiValue {
+    fn from(kansuji: Kansuji) -> Self {
+        let integer = kansuji.to_u128();
+        KansujiValue {
+            integer_high: (integer >> 64) as u64,
+            integer_low: integer as u64,
+            fraction: (kansuji.to_mou() % 1000) as u16,
+        }
+    }
+}
+
+impl TryFrom<KansujiValue> for Kansuji {
+    type Error = KansujiError;
+    fn try_from(value: KansujiValue) -> Result<Self, Self::Error> {
+        let integer = (value.integer_high as u128) << 64 | value.integer_low as u128;
+        if integer >= KANSUJI_LIMIT {
+            return Err(KansujiError::TooLarge);
+        }
+        if value.fraction >= 1000 {
+            return Err(KansujiError::OutOfRange);
+        }
+        Ok(Kansuji::from_mou(integer * 1000 + value.fraction as u128))
+    }
+}
+
+/// 漢数字への変換時の書式
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+pub struct KansujiFormatOptions {
+    /// 大字（壱・弐・参・拾・萬など）を使う
+    pub daiji: bool,
+    /// 千の前の一を省略しない（一千）
+    pub explicit_one: bool,
+}
+
+impl From<KansujiFormatOptions> for KansujiFormat {
+    fn from(options: KansujiFormatOptions) -> Self {
+        KansujiFormat {
+            daiji: options.daiji,
+            explicit_one: options.explicit_one,
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+/// エラーのメッセージを記録し、その種類を返す
+fn fail<E: std::fmt::Display>(status: KansujiStatus, e: E) -> KansujiStatus {
+    // メッセージにNULは含まれない
+    let message = CString::new(e.to_string()).ok();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+    status
+}
+
+fn fail_with(e: KansujiError) -> KansujiStatus {
+    fail(KansujiStatus::from(e.clone()), e)
+}
+
+/// ポインタと長さから文字列のバイト列を得る
+///
+/// # Safety
+///
+/// `s`は`NULL`であるか、`len`バイト読めるものでなければならない。
+unsafe fn input<'a>(s: *const c_char, len: usize) -> Result<&'a [u8], KansujiStatus> {
+    if s.is_null() {
+        if len == 0 {
+            return Ok(&[]);
+        }
+        return Err(fail(KansujiStatus::NullPointer, "null pointer"));
+    }
+    Ok(slice::from_raw_parts(s.cast(), len))
+}
+
+/// 結果をバッファに終端の`NUL`付きで書き込む
+///
+/// `written`には終端の`NUL`を除いた長さを書き込む。
+/// バッファが小さい場合も、必要な長さは`written`に書き込まれる。
+///
+/// # Safety
+///
+/// `buf`は`NULL`であるか、`buf_len`バイト書き込めるものでなければならない。
+/// `written`は`NULL`であるか、書き込めるものでなければならない。
+unsafe fn output(s: &str, buf: *mut c_char, buf_len: usize, written: *mut usize) -> KansujiStatus {
+    if !written.is_null() {
+        *written = s.len();
+    }
+    if buf.is_null() || buf_len <= s.len() {
+        return fail(KansujiStatus::BufferTooSmall, "buffer too small");
+    }
+    ptr::copy_nonoverlapping(s.as_ptr(), buf.cast(), s.len());
+    *buf.add(s.len()) = 0;
+    KansujiStatus::Ok
+}
+
+/// 漢数字を読み、その値を`out`に書き込む
+///
+/// # Safety
+///
+/// `s`は`len`バイト読めるUTF-8の文字列でなければならない（`len`が0の場合は`NULL`でもよい）。
+/// `out`は書き込めるものでなければならない。
+#[no_mangle]
+pub unsafe extern "C" fn kansuji_parse(
+    s: *const c_char,
+    len: usize,
+    out: *mut KansujiValue,
+) -> KansujiStatus {
+    if out.is_null() {
+        return fail(KansujiStatus::NullPointer, "null pointer");
+    }
+    let bytes = match input(s, len) {
+        Ok(bytes) => bytes,
+        Err(status) => return status,
+    };
+    match Kansuji::parse_bytes(bytes) {
+        Ok(kansuji) => {
+            *out = KansujiValue::from(kansuji);
+            KansujiStatus::Ok
+        }
+        Err(e) => fail_with(e),
+    }
+}
+
+/// 漢数字を読み、`12345.6`のような十進の文字列として`buf`に書き込む
+///
+/// `written`には終端の`NUL`を除いた長さを書き込む。`written`は`NULL`でもよい。
+///
+/// # Safety
+///
+/// `s`は`len`バイト読めるUTF-8の文字列でなければならない（`len`が0の場合は`NULL`でもよい）。
+/// `buf`は`buf_len`バイト書き込めるものでなければならない。
+#[no_mangle]
+pub unsafe extern "C" fn kansuji_parse_to_decimal(
+    s: *const c_char,
+    len: usize,
+    buf: *mut c_char,
+    buf_len: usize,
+    written: *mut usize,
+) -> KansujiStatus {
+    let bytes = match input(s, len) {
+        Ok(bytes) => bytes,
+        Err(status) => return status,
+    };
+    match Kansuji::parse_bytes(bytes) {
+        Ok(kansuji) => {
+            let mut decimal = KansujiBuffer::new();
+            // 十進の表記は必ずKansujiBufferに収まる
+            let _ = kansuji.write_decimal(&mut decimal);
+            output(decimal.as_str(), buf, buf_len, written)
+        }
+        Err(e) => fail_with(e),
+    }
+}
+
+/// 値を漢数字にして`buf`に書き込む
+///
+/// `options`が`NULL`の場合は既定の書式とする。
+/// `written`には終端の`NUL`を除いた長さを書き込む。`written`は`NULL`でもよい。
+///
+/// # Safety
+///
+/// `options`は`NULL`であるか、読めるものでなければならない。
+/// `buf`は`buf_len`バイト書き込めるものでなければならない。
+#[no_mangle]
+pub unsafe extern "C" fn kansuji_format(
+    value: KansujiValue,
+    options: *const KansujiFormatOptions,
+    buf: *mut c_char,
+    buf_len: usize,
+    written: *mut usize,
+) -> KansujiStatus {
+    let kansuji = match Kansuji::try_from(value) {
+        Ok(kansuji) => kansuji,
+        Err(e) => return fail_with(e),
+    };
+    let format = options
+        .as_ref()
+        .map_or_else(KansujiFormat::default, |options| (*options).into());
+    let mut kansuji_buffer = KansujiBuffer::new();
+    output(
+        kansuji_buffer.format(kansuji, &format),
+        buf,
+        buf_len,
+        written,
+    )
+}
+
+/// `12345.6`のような十進の文字列を漢数字にして`buf`に書き込む
+///
+/// 小数点以下は三桁までとする。
+/// `options`が`NULL`の場合は既定の書式とする。
+/// `written`には終端の`NUL`を除いた長さを書き込む。`written`は`NULL`でもよい。
+///
+/// # Safety
+///
+/// `s`は`len`バイト読めるUTF-8の文字列でなければならない（`len`が0の場合は`NULL`でもよい）。
+/// `options`は`NULL`であるか、読めるものでなければならない。
+/// `buf`は`buf_len`バイト書き込めるものでなければならない。
+#[no_mangle]
+pub unsafe extern "C" fn kansuji_format_decimal(
+    s: *const c_char,
+    len: usize,
+    options: *const KansujiFormatOptions,
+    buf: *mut c_char,
+    buf_len: usize,
+    written: *mut usize,
+) -> KansujiStatus {
+    let bytes = match input(s, len) {
+        Ok(bytes) => bytes,
+        Err(status) => return status,
+    };
+    let kansuji = match std::str::from_utf8(bytes) {
+        Ok(s) => Kansuji::parse_decimal(s),
+        Err(_) => Err(KansujiError::ParseError),
+    };
+    match kansuji {
+        Ok(kansuji) => kansuji_format(KansujiValue::from(kansuji), options, buf, buf_len, written),
+        Err(e) => fail_with(e),
+    }
+}
+
+/// このスレッドで最後に失敗した関数のエラーメッセージを返す
+///
+/// 返す文字列は終端の`NUL`付きのUTF-8であり、このスレッドで次に関数が失敗するまで有効である。
+/// まだ失敗していない場合は`NULL`を返す。
+#[no_mangle]
+pub extern "C" fn kansuji_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+#[test]
+fn check_ffi_1() {
+    let s = "一二分五厘";
+    let mut value = KansujiValue::default();
+    let status = unsafe { kansuji_parse(s.as_ptr().cast(), s.len(), &mut value) };
+    assert_eq!(status, KansujiStatus::Ok);
+    assert_eq!(
+        value,
+        KansujiValue {
+            integer_high: 0,
+            integer_low: 1,
+            fraction: 250,
+        }
+    );
+
+    let s = "三百円";
+    let status = unsafe { kansuji_parse(s.as_ptr().cast(), s.len(), &mut value) };
+    assert_eq!(status, KansujiStatus::UnexpectedChar);
+    let message = unsafe { std::ffi::CStr::from_ptr(kansuji_last_error_message()) };
+    assert_eq!(message.to_str(), Ok("unexpected char: 円"));
+}
+
+#[test]
+fn check_ffi_2() {
+    assert_eq!(KANSUJI_BUFFER_CAPACITY, KansujiBuffer::CAPACITY + 1);
+    let mut buf = [0 as c_char; KANSUJI_BUFFER_CAPACITY];
+    let mut written = 0;
+    let options = KansujiFormatOptions {
+        daiji: true,
+        explicit_one: false,
+    };
+    let s = "35000";
+    let status = unsafe {
+        kansuji_format_decimal(
+            s.as_ptr().cast(),
+            s.len(),
+            &options,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut written,
+        )
+    };
+    assert_eq!(status, KansujiStatus::Ok);
+    let message = unsafe { std::ffi::CStr::from_ptr(buf.as_ptr()) };
+    assert_eq!(message.to_str(), Ok("参萬伍阡"));
+    assert_eq!(written, "参萬伍阡".len());
+
+    let value = KansujiValue {
+        integer_high: 0,
+        integer_low: 35000,
+        fraction: 0,
+    };
+    let status = unsafe { kansuji_format(value, ptr::null(), buf.as_mut_ptr(), 4, &mut written) };
+    assert_eq!(status, KansujiStatus::BufferTooSmall);
+    assert_eq!(written, "三万五千".len());
+    let value = KansujiValue {
+        integer_high: u64::MAX,
+        ..value
+    };
+    let status = unsafe {
+        kansuji_format(
+            value,
+            ptr::null(),
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut written,
+        )
+    };
+    assert_eq!(status, KansujiStatus::TooLarge);
+    let value = KansujiValue {
+        integer_high: 0,
+        fraction: 1000,
+        ..value
+    };
+    let status = unsafe {
+        kansuji_format(
+            value,
+            ptr::null(),
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut written,
+        )
+    };
+    assert_eq!(status, KansujiStatus::OutOfRange);
+}
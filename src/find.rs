@@ -0,0 +1,215 @@
+//! 文字列の中から漢数字を探す
+//!
+//! 数字と位（[`TokenKind::is_numeral`]）が続く部分のうち、漢数字として読めるものを先頭から順に返す。
+//! 算用数字だけの部分（`2024`など）は漢数字ではないため含めない。
+//!
+//! `千葉`・`統一`・`七夕`のように漢数字一文字が語の一部になっていることが多いため、
+//! 一文字だけの漢数字は、後ろに`円`・`個`・`人`・`年`などの助数詞や単位、英字、`%`が続く場合に限って返す。
+//! 助数詞と単位は[`JosuushiTable`]の既定の一覧にあるものとし、独自の一覧を使う場合は[`JosuushiTable::find_iter`]を使う。
+//! そのため`第三`や文末の`三`のような一文字の漢数字は見つからない。
+//! また、`万一`・`億万`のように万・億などの位で始まるものと、`三日月`のように助数詞が続いても数ではない語は含めない。
+//!
+//! ```
+//! use kansuji::find_iter;
+//!
+//! let found: Vec<(&str, u128)> = find_iter("定価三百二十円、在庫は1万2千個")
+//!     .map(|m| (m.as_str(), u128::from(m.kansuji())))
+//!     .collect();
+//! assert_eq!(found, vec![("三百二十", 320), ("1万2千", 12000)]);
+//! ```
+
+use super::{arabic_digit, JosuushiTable, Kansuji, TokenKind};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::ops::Range;
+
+/// 漢数字と助数詞で始まるが、数としては読まない語
+const IDIOMS: &[&str] = &["三日月", "三日坊主", "十人十色", "一日千秋"];
+
+/// 見つかった漢数字
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct KansujiMatch<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+    kansuji: Kansuji,
+}
+
+impl<'a> KansujiMatch<'a> {
+    /// 元の表記を返す
+    pub fn as_str(&self) -> &'a str {
+        &self.text[self.start..self.end]
+    }
+
+    /// 元の文字列での開始位置（バイト）を返す
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// 元の文字列での終了位置（バイト）を返す
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// 元の文字列でのバイト位置を返す
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// 値を返す
+    pub fn kansuji(&self) -> Kansuji {
+        self.kansuji
+    }
+}
+
+/// [`find_iter`]が返すイテレータ
+#[derive(Clone, Debug)]
+pub struct Matches<'a> {
+    text: &'a str,
+    pos: usize,
+    table: Cow<'a, JosuushiTable>,
+}
+
+impl<'a> Matches<'a> {
+    pub(crate) fn new(text: &'a str, table: Cow<'a, JosuushiTable>) -> Self {
+        Matches {
+            text,
+            pos: 0,
+            table,
+        }
+    }
+
+    /// 一文字だけの漢数字の後ろに続いたときに、数として扱う助数詞や単位かどうかを判定する
+    fn is_counter(&self, rest: &str) -> bool {
+        self.table.find_prefix(rest).is_some()
+            || rest
+                .chars()
+                .next()
+                .map_or(false, |c| c.is_ascii_alphabetic() || c == '%')
+    }
+}
+
+/// 文字列の中の漢数字を先頭から順に返す
+pub fn find_iter(text: &str) -> Matches<'_> {
+    Matches::new(text, Cow::Owned(JosuushiTable::default()))
+}
+
+impl<'a> Iterator for Matches<'a> {
+    type Item = KansujiMatch<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.text.len() {
+            let rest = &self.text[self.pos..];
+            let start = match rest
+                .char_indices()
+                .find(|(_, c)| TokenKind::classify(*c).is_numeral())
+            {
+                Some((i, _)) => self.pos + i,
+                None => {
+                    self.pos = self.text.len();
+                    return None;
+                }
+            };
+            let run = &self.text[start..];
+            let len = run
+                .char_indices()
+                .find(|(_, c)| !TokenKind::classify(*c).is_numeral())
+                .map_or(run.len(), |(i, _)| i);
+            if let Some(idiom) = IDIOMS.iter().find(|idiom| run.starts_with(*idiom)) {
+                self.pos = start + idiom.len();
+                continue;
+            }
+            let end = start + len;
+            self.pos = end;
+            let run = &run[..len];
+            // 算用数字だけのものは漢数字としない
+            if run.chars().all(|c| arabic_digit(c).is_some()) {
+                continue;
+            }
+            if run.chars().nth(1).is_none() && !self.is_counter(&self.text[end..]) {
+                continue;
+            }
+            if let Ok(kansuji) = Kansuji::try_from(run) {
+                return Some(KansujiMatch {
+                    text: self.text,
+                    start,
+                    end,
+                    kansuji,
+                });
+            }
+        }
+        None
+    }
+}
+
+#[test]
+fn check_find_iter_1() {
+    let mut matches = find_iter("三百二十円と一万二千五百円");
+    let m = matches.next().unwrap();
+    assert_eq!(m.as_str(), "三百二十");
+    assert_eq!(m.range(), 0..12);
+    let m = matches.next().unwrap();
+    assert_eq!(m.kansuji(), Kansuji::from(12500_u16));
+    assert_eq!(m.start(), 18);
+    assert_eq!(matches.next(), None);
+}
+
+#[test]
+fn check_find_iter_2() {
+    assert_eq!(find_iter("2024年").count(), 0);
+    assert_eq!(find_iter("羊毛と三三万").count(), 0);
+    assert_eq!(find_iter("").count(), 0);
+    let matches = find_iter("１２万人と一二分五厘").map(|m| m.as_str());
+    assert!(matches.eq(["１２万", "一二分五厘"]));
+}
+
+#[test]
+fn check_find_iter_3() {
+    assert_eq!(find_iter("千葉県").count(), 0);
+    assert_eq!(find_iter("一般的に統一する").count(), 0);
+    assert_eq!(find_iter("七夕と一石二鳥").count(), 0);
+    assert_eq!(find_iter("第三").count(), 0);
+    assert_eq!(find_iter("万一の場合は三日月").count(), 0);
+    assert_eq!(find_iter("億万長者が十人十色").count(), 0);
+    assert!(find_iter("三日と一万円")
+        .map(|m| m.as_str())
+        .eq(["三", "一万"]));
+    let matches = find_iter("三人で五kgと十").map(|m| m.as_str());
+    assert!(matches.eq(["三", "五"]));
+}
+
+/// 文字列の中の漢数字をすべて置き換える
+///
+/// ```
+/// use kansuji::replace_all;
+///
+/// let s = replace_all("定価三百二十円", |m| u128::from(m.kansuji()).to_string());
+/// assert_eq!(s, "定価320円");
+/// ```
+pub fn replace_all<F, R>(text: &str, mut replacement: F) -> String
+where
+    F: FnMut(&KansujiMatch) -> R,
+    R: AsRef<str>,
+{
+    let mut replaced = String::with_capacity(text.len());
+    let mut last = 0;
+    for m in find_iter(text) {
+        replaced.push_str(&text[last..m.start()]);
+        replaced.push_str(replacement(&m).as_ref());
+        last = m.end();
+    }
+    replaced.push_str(&text[last..]);
+    replaced
+}
+
+#[test]
+fn check_replace_all_1() {
+    use alloc::string::ToString;
+
+    let s = replace_all("三百二十円と一万二千五百円\n", |m| {
+        m.kansuji().to_u128().to_string()
+    });
+    assert_eq!(s, "320円と12500円\n");
+    assert_eq!(replace_all("2024年", |_| "x"), "2024年");
+    assert_eq!(replace_all("千葉県で一つ", |_| "x"), "千葉県でxつ");
+}
@@ -0,0 +1,318 @@
+//! 三〜五人・十から二十まで・二、三日のような数の範囲と列挙の解析を行う
+//!
+//! `五、六百`や`三〜五万`のように後ろの数にだけ付いた位は、前の数にも付けて読む。
+//!
+//! ```
+//! use kansuji::Hanni;
+//!
+//! let hanni = Hanni::try_from("三〜五人").unwrap();
+//! assert_eq!(u128::from(hanni.start()), 3);
+//! assert_eq!(u128::from(hanni.end()), 5);
+//! assert_eq!(hanni.counter(), Some("人"));
+//!
+//! let hanni = Hanni::try_from("五、六百").unwrap();
+//! assert_eq!(u128::from(hanni.start()), 500);
+//! assert_eq!(u128::from(hanni.end()), 600);
+//! assert!(hanni.is_enumeration());
+//! ```
+
+use super::{
+    arabic_digit, is_numeral_char, parse_kansuji_or_decimal, Kansuji, KansujiError, TokenKind,
+};
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::convert::TryFrom;
+use core::fmt;
+
+/// 数の範囲
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Hanni {
+    始め: Kansuji,
+    終わり: Kansuji,
+    列挙: bool,
+    助数詞: Option<String>,
+}
+
+impl Hanni {
+    /// 始めと終わりの数から範囲を作る
+    pub fn new(start: Kansuji, end: Kansuji) -> Result<Self, KansujiError> {
+        if start.to_mou() > end.to_mou() {
+            return Err(KansujiError::OutOfRange);
+        }
+        Ok(Hanni {
+            始め: start,
+            終わり: end,
+            列挙: false,
+            助数詞: None,
+        })
+    }
+
+    /// 範囲の始めの数を返す
+    pub fn start(&self) -> Kansuji {
+        self.始め
+    }
+
+    /// 範囲の終わりの数を返す（範囲に含む）
+    pub fn end(&self) -> Kansuji {
+        self.終わり
+    }
+
+    /// `二、三日`のように数を並べて書いたものかどうかを返す
+    pub fn is_enumeration(&self) -> bool {
+        self.列挙
+    }
+
+    /// 数の後ろに付いた助数詞を返す
+    pub fn counter(&self) -> Option<&str> {
+        self.助数詞.as_deref()
+    }
+}
+
+impl TryFrom<String> for Hanni {
+    type Error = KansujiError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_hanni(&value)
+    }
+}
+
+impl TryFrom<&String> for Hanni {
+    type Error = KansujiError;
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        parse_hanni(value)
+    }
+}
+
+impl TryFrom<&str> for Hanni {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_hanni(value)
+    }
+}
+
+/// 範囲を表す区切りと、列挙かどうか
+///
+/// 中黒（`・`）は読点と同じく列挙の区切りとし、小数点としては読まない。
+/// 列挙は二つの数に限り、`二、三、四日`のように三つ以上並べたものはエラーとする。
+const HANNI_SEPARATORS: [(&str, bool); 6] = [
+    ("〜", false),
+    ("～", false),
+    ("~", false),
+    ("から", false),
+    ("、", true),
+    ("・", true),
+];
+
+/// 万以上の位を表す文字かどうかを判定する
+fn is_myriad_char(c: char) -> bool {
+    matches!(TokenKind::classify(c), TokenKind::MyriadUnit(_))
+}
+
+/// 先頭の漢数字とその後ろの文字列に分ける
+fn split_numeral(s: &str) -> Result<(&str, &str), KansujiError> {
+    let i = s
+        .char_indices()
+        .find(|(_, c)| !is_numeral_char(*c))
+        .map_or(s.len(), |(i, _)| i);
+    let (n, rest) = s.split_at(i);
+    if n.is_empty() {
+        return Err(rest
+            .chars()
+            .next()
+            .map_or(KansujiError::UnexpectedEnd, KansujiError::UnexpectedChar));
+    }
+    Ok((n, rest))
+}
+
+/// 後ろの数にだけ付いた位を前の数に付ける
+///
+/// 位を付けると前の数が後ろの数の係数より小さい場合にだけ付ける。
+/// `一〜一万`は一〜一万、`1〜15個`は1〜15のままとする。
+fn propagate_unit(start: &str, end: &str) -> String {
+    // 五、六百は五百、六百とする（算用数字は位を付けない）
+    let kanji_digit = |c: char| match TokenKind::classify(c) {
+        TokenKind::Digit(d) if arabic_digit(c).is_none() => Some(d),
+        _ => None,
+    };
+    let mut start_chars = start.chars();
+    if let (Some(s), None) = (start_chars.next().and_then(kanji_digit), start_chars.next()) {
+        let mut end_chars = end.chars();
+        if let Some(e) = end_chars.next().and_then(kanji_digit) {
+            let units = end_chars.as_str();
+            if s < e && !units.is_empty() && units.chars().all(is_keta_char) {
+                return format!("{}{}", start, units);
+            }
+        }
+    }
+    // 十〜二十万は十万〜二十万とする
+    if !start.chars().any(is_myriad_char) {
+        let i = end
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| is_myriad_char(*c))
+            .last()
+            .map(|(i, _)| i);
+        if let Some(i) = i {
+            let smaller = match (
+                parse_kansuji_or_decimal(start),
+                parse_kansuji_or_decimal(&end[..i]),
+            ) {
+                (Ok(start), Ok(coefficient)) => start.to_mou() < coefficient.to_mou(),
+                _ => false,
+            };
+            if smaller {
+                return format!("{}{}", start, &end[i..]);
+            }
+        }
+    }
+    start.to_string()
+}
+
+/// 十・百・千などの位を表す文字かどうかを判定する
+fn is_keta_char(c: char) -> bool {
+    matches!(
+        TokenKind::classify(c),
+        TokenKind::SmallUnit(_) | TokenKind::MyriadUnit(_)
+    )
+}
+
+fn parse_hanni(s: &str) -> Result<Hanni, KansujiError> {
+    let (start, rest) = split_numeral(s)?;
+    let (start_counter, separator, enumeration) = HANNI_SEPARATORS
+        .iter()
+        .filter_map(|(sep, e)| rest.find(sep).map(|i| (i, *sep, *e)))
+        .min_by_key(|(i, _, _)| *i)
+        .map(|(i, sep, e)| (&rest[..i], sep, e))
+        .ok_or_else(|| {
+            rest.chars()
+                .next()
+                .map_or(KansujiError::UnexpectedEnd, KansujiError::UnexpectedChar)
+        })?;
+    let rest = &rest[start_counter.len() + separator.len()..];
+    let (end, rest) = split_numeral(rest)?;
+    let counter = if separator == "から" {
+        rest.strip_suffix("まで").unwrap_or(rest)
+    } else {
+        rest
+    };
+    // 助数詞の中に区切りが残る場合は、三つ以上の数が並んでいる
+    if let Some(i) = HANNI_SEPARATORS
+        .iter()
+        .filter_map(|(sep, _)| counter.find(sep))
+        .min()
+    {
+        return Err(counter[i..]
+            .chars()
+            .next()
+            .map_or(KansujiError::ParseError, KansujiError::UnexpectedChar));
+    }
+    // 三人〜五人のように両方に同じ助数詞が付いたものは許す
+    if !start_counter.is_empty() && start_counter != counter {
+        return Err(start_counter
+            .chars()
+            .next()
+            .map_or(KansujiError::ParseError, KansujiError::UnexpectedChar));
+    }
+    let start = if start_counter.is_empty() {
+        propagate_unit(start, end)
+    } else {
+        start.to_string()
+    };
+    let mut hanni = Hanni::new(
+        parse_kansuji_or_decimal(&start)?,
+        parse_kansuji_or_decimal(end)?,
+    )
+    .map_err(|_| KansujiError::ParseError)?;
+    hanni.列挙 = enumeration;
+    if !counter.is_empty() {
+        hanni.助数詞 = Some(counter.to_string());
+    }
+    Ok(hanni)
+}
+
+#[test]
+fn check_parse_hanni_1() {
+    let v = [
+        ("三〜五人", 3, 5, Some("人")),
+        ("三～五", 3, 5, None),
+        ("十から二十", 10, 20, None),
+        ("十から二十まで", 10, 20, None),
+        ("十から二十人まで", 10, 20, Some("人")),
+        ("三人から五人", 3, 5, Some("人")),
+        ("二、三日", 2, 3, Some("日")),
+        ("五、六百", 500, 600, None),
+        ("二・三百万円", 2_000_000, 3_000_000, Some("円")),
+        ("三〜五万", 30_000, 50_000, None),
+        ("十〜二十万人", 100_000, 200_000, Some("人")),
+        ("八百〜千二百", 800, 1200, None),
+        ("3〜5個", 3, 5, Some("個")),
+        ("1〜15個", 1, 15, Some("個")),
+        ("3〜10個", 3, 10, Some("個")),
+        ("9〜12時", 9, 12, Some("時")),
+        ("1〜10000", 1, 10000, None),
+        ("一〜一万", 1, 10000, None),
+        ("3〜5万", 30_000, 50_000, None),
+    ];
+    for (s, start, end, counter) in v.iter() {
+        let hanni = parse_hanni(s).unwrap();
+        assert_eq!(u128::from(hanni.start()), *start);
+        assert_eq!(u128::from(hanni.end()), *end);
+        assert_eq!(hanni.counter(), *counter);
+    }
+}
+
+#[test]
+fn check_parse_hanni_2() {
+    assert_eq!(parse_hanni(""), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_hanni("三"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_hanni("三人"), Err(KansujiError::UnexpectedChar('人')));
+    assert_eq!(parse_hanni("三〜"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_hanni("五〜三"), Err(KansujiError::ParseError));
+    assert_eq!(
+        parse_hanni("三個〜五人"),
+        Err(KansujiError::UnexpectedChar('個'))
+    );
+    assert_eq!(
+        parse_hanni("二、三、四日"),
+        Err(KansujiError::UnexpectedChar('、'))
+    );
+    assert_eq!(
+        parse_hanni("二・三・四人"),
+        Err(KansujiError::UnexpectedChar('・'))
+    );
+    assert_eq!(
+        parse_hanni("三〜五人から"),
+        Err(KansujiError::UnexpectedChar('か'))
+    );
+    assert!(!parse_hanni("三〜五").unwrap().is_enumeration());
+    assert!(parse_hanni("二、三").unwrap().is_enumeration());
+}
+
+impl fmt::Display for Hanni {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = if self.列挙 { "、" } else { "〜" };
+        write!(f, "{}{}{}", self.始め, separator, self.終わり)?;
+        if let Some(counter) = &self.助数詞 {
+            write!(f, "{}", counter)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn check_hanni_1() {
+    fn hanni_test_function((s, t): &(&str, &str)) {
+        assert_eq!(Hanni::try_from(*s).unwrap().to_string(), *t);
+    }
+
+    let v = [
+        ("三〜五人", "三〜五人"),
+        ("十から二十まで", "十〜二十"),
+        ("二、三日", "二、三日"),
+        ("五、六百", "五百、六百"),
+    ];
+    v.iter().for_each(hanni_test_function);
+    assert_eq!(
+        Hanni::new(Kansuji::from(5_u8), Kansuji::from(3_u8)),
+        Err(KansujiError::OutOfRange)
+    );
+}
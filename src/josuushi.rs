@@ -0,0 +1,411 @@
+//! 助数詞の付いた数の解析と読みの生成を行う
+//!
+//! 助数詞は[`JosuushiTable`]に登録されたものを認識し、登録されていないものはそのままの文字列として扱う。
+//! 一つ〜十（とお）やひとつ〜ここのつのような和語の数え方にも対応する。
+//!
+//! ```
+//! use kansuji::JosuushiTable;
+//!
+//! let table = JosuushiTable::default();
+//! let suuryou = table.parse("三本").unwrap();
+//! assert_eq!(suuryou.counter(), Some("本"));
+//! assert_eq!(table.reading(&suuryou), "さんぼん");
+//!
+//! assert_eq!(table.reading(&table.parse("一匹").unwrap()), "いっぴき");
+//! assert_eq!(table.reading(&table.parse("二十歳").unwrap()), "はたち");
+//! assert_eq!(u128::from(table.parse("ひとつ").unwrap().kansuji()), 1);
+//! ```
+
+use super::yomi::Onbin;
+use super::{is_numeral_char, Kansuji, KansujiError, Matches};
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::{From, TryFrom};
+use core::fmt;
+
+/// 助数詞
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct Josuushi {
+    /// 表記
+    pub counter: String,
+    /// 読み
+    pub reading: String,
+    /// 数の後ろに付いたときの音便
+    pub onbin: Onbin,
+    /// 数と助数詞を合わせた読みが特殊なもの（一人（ひとり）・二十歳（はたち）など）
+    pub exceptions: Vec<(u128, String)>,
+    /// 一の位の数字の読みが特殊なもの（四人（よにん）など）
+    pub ones_readings: Vec<(u8, String)>,
+}
+
+impl Josuushi {
+    /// 特殊な読みのない助数詞を作る
+    pub fn new(counter: &str, reading: &str, onbin: Onbin) -> Self {
+        Josuushi {
+            counter: counter.to_string(),
+            reading: reading.to_string(),
+            onbin,
+            exceptions: Vec::new(),
+            ones_readings: Vec::new(),
+        }
+    }
+
+    fn with_exceptions(mut self, exceptions: &[(u128, &str)]) -> Self {
+        self.exceptions = exceptions
+            .iter()
+            .map(|(n, s)| (*n, s.to_string()))
+            .collect();
+        self
+    }
+
+    fn with_ones_readings(mut self, ones_readings: &[(u8, &str)]) -> Self {
+        self.ones_readings = ones_readings
+            .iter()
+            .map(|(n, s)| (*n, s.to_string()))
+            .collect();
+        self
+    }
+}
+
+/// 和語の数え方（ひとつ〜とお）
+const WAGO: [(u128, &str); 10] = [
+    (1, "ひとつ"),
+    (2, "ふたつ"),
+    (3, "みっつ"),
+    (4, "よっつ"),
+    (5, "いつつ"),
+    (6, "むっつ"),
+    (7, "ななつ"),
+    (8, "やっつ"),
+    (9, "ここのつ"),
+    (10, "とお"),
+];
+
+/// 助数詞の一覧
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct JosuushiTable {
+    entries: Vec<Josuushi>,
+}
+
+impl Default for JosuushiTable {
+    /// よく使われる助数詞を登録した一覧を作る
+    fn default() -> Self {
+        let mut table = JosuushiTable::new();
+        for josuushi in [
+            Josuushi::new("つ", "つ", Onbin::None).with_exceptions(&WAGO),
+            Josuushi::new("人", "にん", Onbin::None)
+                .with_exceptions(&[(1, "ひとり"), (2, "ふたり")])
+                .with_ones_readings(&[(4, "よ")]),
+            Josuushi::new("本", "ほん", Onbin::Ha),
+            Josuushi::new("匹", "ひき", Onbin::Ha),
+            Josuushi::new("杯", "はい", Onbin::Ha),
+            Josuushi::new("分", "ふん", Onbin::HaHandakuon),
+            Josuushi::new("個", "こ", Onbin::Ka),
+            Josuushi::new("回", "かい", Onbin::Ka),
+            Josuushi::new("件", "けん", Onbin::Ka),
+            Josuushi::new("ヶ月", "かげつ", Onbin::Ka),
+            Josuushi::new("か月", "かげつ", Onbin::Ka),
+            Josuushi::new("階", "かい", Onbin::KaDakuon),
+            Josuushi::new("軒", "けん", Onbin::KaDakuon),
+            Josuushi::new("冊", "さつ", Onbin::SaTa),
+            Josuushi::new("歳", "さい", Onbin::SaTa).with_exceptions(&[(20, "はたち")]),
+            Josuushi::new("才", "さい", Onbin::SaTa).with_exceptions(&[(20, "はたち")]),
+            Josuushi::new("点", "てん", Onbin::SaTa),
+            Josuushi::new("頭", "とう", Onbin::SaTa),
+            Josuushi::new("着", "ちゃく", Onbin::SaTa),
+            Josuushi::new("通", "つう", Onbin::SaTa),
+            Josuushi::new("枚", "まい", Onbin::None),
+            Josuushi::new("台", "だい", Onbin::None),
+            Josuushi::new("秒", "びょう", Onbin::None),
+            Josuushi::new("度", "ど", Onbin::None),
+            Josuushi::new("番", "ばん", Onbin::None),
+            Josuushi::new("倍", "ばい", Onbin::None),
+            Josuushi::new("円", "えん", Onbin::None).with_ones_readings(&[(4, "よ")]),
+            Josuushi::new("年", "ねん", Onbin::None).with_ones_readings(&[(4, "よ")]),
+            Josuushi::new("時", "じ", Onbin::None).with_ones_readings(&[(4, "よ"), (9, "く")]),
+            Josuushi::new("日", "にち", Onbin::None).with_exceptions(&[
+                (2, "ふつか"),
+                (3, "みっか"),
+                (4, "よっか"),
+                (5, "いつか"),
+                (6, "むいか"),
+                (7, "なのか"),
+                (8, "ようか"),
+                (9, "ここのか"),
+                (10, "とおか"),
+                (14, "じゅうよっか"),
+                (20, "はつか"),
+                (24, "にじゅうよっか"),
+            ]),
+            Josuushi::new("月", "がつ", Onbin::None).with_ones_readings(&[
+                (4, "し"),
+                (7, "しち"),
+                (9, "く"),
+            ]),
+            Josuushi::new("週", "しゅう", Onbin::SaTa),
+            Josuushi::new("名", "めい", Onbin::None),
+            Josuushi::new("銭", "せん", Onbin::SaTa),
+            Josuushi::new("割", "わり", Onbin::None),
+            Josuushi::new("号", "ごう", Onbin::None),
+            Josuushi::new("条", "じょう", Onbin::None),
+            Josuushi::new("項", "こう", Onbin::Ka),
+            Josuushi::new("章", "しょう", Onbin::SaTa),
+            Josuushi::new("節", "せつ", Onbin::SaTa),
+            Josuushi::new("位", "い", Onbin::None),
+            Josuushi::new("社", "しゃ", Onbin::SaTa),
+            Josuushi::new("組", "くみ", Onbin::None),
+            Josuushi::new("丁", "ちょう", Onbin::SaTa),
+            Josuushi::new("畳", "じょう", Onbin::None),
+            Josuushi::new("坪", "つぼ", Onbin::None),
+            Josuushi::new("歩", "ぶ", Onbin::None),
+            Josuushi::new("尺", "しゃく", Onbin::SaTa),
+            Josuushi::new("寸", "すん", Onbin::None),
+            Josuushi::new("升", "しょう", Onbin::SaTa),
+            Josuushi::new("合", "ごう", Onbin::None),
+            Josuushi::new("斗", "と", Onbin::SaTa),
+            Josuushi::new("里", "り", Onbin::None),
+            Josuushi::new("町", "ちょう", Onbin::SaTa),
+            Josuushi::new("貫", "かん", Onbin::Ka),
+            Josuushi::new("匁", "もんめ", Onbin::None),
+        ] {
+            table.insert(josuushi);
+        }
+        table
+    }
+}
+
+impl JosuushiTable {
+    /// 空の一覧を作る
+    pub fn new() -> Self {
+        JosuushiTable {
+            entries: Vec::new(),
+        }
+    }
+
+    /// 助数詞を登録する
+    ///
+    /// 同じ表記の助数詞が既にある場合は置き換える。
+    pub fn insert(&mut self, josuushi: Josuushi) {
+        self.entries.retain(|j| j.counter != josuushi.counter);
+        self.entries.push(josuushi);
+        // 長い表記から順に照合するため
+        self.entries
+            .sort_by_key(|j| core::cmp::Reverse(j.counter.len()));
+    }
+
+    /// 表記から助数詞を探す
+    pub fn get(&self, counter: &str) -> Option<&Josuushi> {
+        self.entries.iter().find(|j| j.counter == counter)
+    }
+
+    /// 文字列の先頭にある助数詞を探す
+    ///
+    /// 複数の助数詞に当てはまる場合は、表記の長いものを返す。
+    pub fn find_prefix(&self, s: &str) -> Option<&Josuushi> {
+        self.entries
+            .iter()
+            .find(|j| s.starts_with(j.counter.as_str()))
+    }
+
+    /// 文字列の中の漢数字を先頭から順に返す
+    ///
+    /// [`find_iter`](crate::find_iter)と同じく、一文字だけの漢数字は後ろに助数詞や単位が続く場合に限って返す。
+    /// 助数詞にはこの一覧に登録されたものを使う。
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> Matches<'a> {
+        Matches::new(text, Cow::Borrowed(self))
+    }
+
+    /// 助数詞の付いた数を読む
+    ///
+    /// 数の後ろが登録されていない文字列の場合は、その文字列を未知の助数詞として扱う。
+    pub fn parse(&self, s: &str) -> Result<Suuryou, KansujiError> {
+        if self.get("つ").is_some() {
+            if let Some((n, _)) = WAGO.iter().find(|(_, w)| *w == s) {
+                return Ok(Suuryou {
+                    数: Kansuji::from(*n),
+                    助数詞: Some("つ".to_string()),
+                    既知: true,
+                });
+            }
+        }
+        for josuushi in self.entries.iter() {
+            if let Some(n) = s.strip_suffix(josuushi.counter.as_str()) {
+                if n.is_empty() {
+                    continue;
+                }
+                if let Ok(kansuji) = Kansuji::try_from(n) {
+                    return Ok(Suuryou {
+                        数: kansuji,
+                        助数詞: Some(josuushi.counter.clone()),
+                        既知: true,
+                    });
+                }
+            }
+        }
+        let i = s
+            .char_indices()
+            .find(|(_, c)| !is_numeral_char(*c))
+            .map_or(s.len(), |(i, _)| i);
+        let (n, counter) = s.split_at(i);
+        if n.is_empty() {
+            return Err(counter
+                .chars()
+                .next()
+                .map_or(KansujiError::UnexpectedEnd, KansujiError::UnexpectedChar));
+        }
+        Ok(Suuryou {
+            数: Kansuji::try_from(n)?,
+            助数詞: if counter.is_empty() {
+                None
+            } else {
+                Some(counter.to_string())
+            },
+            既知: false,
+        })
+    }
+
+    /// 助数詞の付いた数の読みをひらがなで返す
+    ///
+    /// 未知の助数詞はそのまま読みの後ろに付ける。
+    pub fn reading(&self, suuryou: &Suuryou) -> String {
+        let counter = match &suuryou.助数詞 {
+            Some(counter) => counter,
+            None => return suuryou.数.reading(),
+        };
+        let josuushi = match self.get(counter) {
+            Some(josuushi) => josuushi,
+            None => return format!("{}{}", suuryou.数.reading(), counter),
+        };
+        if !suuryou.数.has_fraction() {
+            let n: u128 = suuryou.数.into();
+            if let Some((_, r)) = josuushi.exceptions.iter().find(|(m, _)| *m == n) {
+                return r.clone();
+            }
+        }
+        suuryou
+            .数
+            .reading_with(&josuushi.reading, josuushi.onbin, &josuushi.ones_readings)
+    }
+}
+
+#[test]
+fn check_josuushi_table_1() {
+    let table = JosuushiTable::default();
+    let v = [
+        ("三本", "さんぼん"),
+        ("一匹", "いっぴき"),
+        ("六匹", "ろっぴき"),
+        ("二十歳", "はたち"),
+        ("二十一歳", "にじゅういっさい"),
+        ("一人", "ひとり"),
+        ("四人", "よにん"),
+        ("十四人", "じゅうよにん"),
+        ("三階", "さんがい"),
+        ("八冊", "はっさつ"),
+        ("三ヶ月", "さんかげつ"),
+        ("十分", "じゅっぷん"),
+        ("九時", "くじ"),
+        ("一つ", "ひとつ"),
+        ("ななつ", "ななつ"),
+        ("三百", "さんびゃく"),
+    ];
+    for (s, r) in v.iter() {
+        let suuryou = table.parse(s).unwrap();
+        assert_eq!(table.reading(&suuryou), *r);
+    }
+}
+
+#[test]
+fn check_josuushi_table_2() {
+    let table = JosuushiTable::default();
+    let suuryou = table.parse("五羽").unwrap();
+    assert_eq!(u128::from(suuryou.kansuji()), 5);
+    assert_eq!(suuryou.counter(), Some("羽"));
+    assert!(!suuryou.is_known());
+    assert_eq!(table.reading(&suuryou), "ご羽");
+
+    let suuryou = table.parse("三万円").unwrap();
+    assert_eq!(u128::from(suuryou.kansuji()), 30000);
+    assert!(suuryou.is_known());
+
+    let suuryou = table.parse("三十").unwrap();
+    assert_eq!(suuryou.counter(), None);
+    assert_eq!(suuryou.to_string(), "三十");
+
+    assert_eq!(table.parse("本"), Err(KansujiError::UnexpectedChar('本')));
+    assert_eq!(table.reading(&table.parse("三日").unwrap()), "みっか");
+    assert_eq!(table.reading(&table.parse("九月").unwrap()), "くがつ");
+    assert_eq!(table.reading(&table.parse("一升").unwrap()), "いっしょう");
+    assert_eq!(table.parse(""), Err(KansujiError::UnexpectedEnd));
+}
+
+#[test]
+fn check_josuushi_table_3() {
+    let mut table = JosuushiTable::new();
+    assert!(!table.parse("三本").unwrap().is_known());
+    table.insert(Josuushi::new("羽", "わ", Onbin::None));
+    let suuryou = table.parse("三羽").unwrap();
+    assert!(suuryou.is_known());
+    assert_eq!(table.reading(&suuryou), "さんわ");
+    assert_eq!(suuryou.to_string(), "三羽");
+}
+
+#[test]
+fn check_josuushi_table_4() {
+    let mut table = JosuushiTable::default();
+    assert_eq!(
+        table.find_prefix("ヶ月後").map(|j| j.reading.as_str()),
+        Some("かげつ")
+    );
+    assert_eq!(table.find_prefix("羽"), None);
+    assert_eq!(
+        table
+            .find_iter("三羽と五本")
+            .map(|m| m.as_str())
+            .collect::<Vec<_>>(),
+        ["五"]
+    );
+    table.insert(Josuushi::new("羽", "わ", Onbin::None));
+    assert!(table
+        .find_iter("三羽と五本")
+        .map(|m| m.as_str())
+        .eq(["三", "五"]));
+}
+
+/// 助数詞の付いた数
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Suuryou {
+    数: Kansuji,
+    助数詞: Option<String>,
+    既知: bool,
+}
+
+impl Suuryou {
+    /// 数を返す
+    pub fn kansuji(&self) -> Kansuji {
+        self.数
+    }
+
+    /// 助数詞を返す
+    pub fn counter(&self) -> Option<&str> {
+        self.助数詞.as_deref()
+    }
+
+    /// 助数詞が一覧に登録されたものかどうかを返す
+    pub fn is_known(&self) -> bool {
+        self.既知
+    }
+}
+
+impl fmt::Display for Suuryou {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.助数詞.as_deref() {
+            // 十は「十つ」とは書かない
+            Some("つ") if u128::from(self.数) == 10 => write!(f, "{}", self.数),
+            Some(counter) => write!(f, "{}{}", self.数, counter),
+            None => write!(f, "{}", self.数),
+        }
+    }
+}
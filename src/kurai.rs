@@ -0,0 +1,200 @@
+//! 漢数字を位（垓・京・兆・億・万・一・分・厘・毛）ごとに読み書きする
+//!
+//! 万以上の位と一の位は四桁ごとのまとまり（0〜9999）、分・厘・毛は一桁（0〜9）の値を持つ。
+//!
+//! ```
+//! use kansuji::{Kansuji, Kurai};
+//!
+//! let kansuji = Kansuji::from(3_2500_0800_u64);
+//! assert_eq!(kansuji.get(Kurai::億), 3);
+//! assert_eq!(kansuji.get(Kurai::万), 2500);
+//! assert_eq!(kansuji.get(Kurai::一), 800);
+//!
+//! let kansuji = Kansuji::builder()
+//!     .set(Kurai::億, 3)
+//!     .set(Kurai::万, 2500)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(u128::from(kansuji), 3_2500_0000);
+//! ```
+
+use super::{Kansuji, KansujiError};
+use core::convert::TryFrom;
+
+/// 漢数字の位
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Kurai {
+    垓,
+    京,
+    兆,
+    億,
+    万,
+    一,
+    分,
+    厘,
+    毛,
+}
+
+impl Kurai {
+    /// すべての位（大きい順）
+    pub const ALL: [Kurai; 9] = [
+        Kurai::垓,
+        Kurai::京,
+        Kurai::兆,
+        Kurai::億,
+        Kurai::万,
+        Kurai::一,
+        Kurai::分,
+        Kurai::厘,
+        Kurai::毛,
+    ];
+
+    /// 小数点以下の位（分・厘・毛）かどうかを返す
+    pub fn is_fraction(self) -> bool {
+        matches!(self, Kurai::分 | Kurai::厘 | Kurai::毛)
+    }
+
+    /// その位の一を毛（千分の一）で数えた大きさを返す
+    #[inline]
+    pub(crate) fn place(self) -> u128 {
+        match self {
+            Kurai::垓 => 1_0000_0000_0000_0000_0000 * 1000,
+            Kurai::京 => 1_0000_0000_0000_0000 * 1000,
+            Kurai::兆 => 1_0000_0000_0000 * 1000,
+            Kurai::億 => 1_0000_0000 * 1000,
+            Kurai::万 => 1_0000 * 1000,
+            Kurai::一 => 1000,
+            Kurai::分 => 100,
+            Kurai::厘 => 10,
+            Kurai::毛 => 1,
+        }
+    }
+
+    /// その位が持てる値の上限（含まない）を返す
+    fn limit(self) -> u16 {
+        if self.is_fraction() {
+            10
+        } else {
+            10000
+        }
+    }
+}
+
+impl Kansuji {
+    /// 位の値を返す
+    #[inline]
+    pub fn get(&self, kurai: Kurai) -> u16 {
+        // u64に収まる場合はu128の除算を避ける
+        match (u64::try_from(self.値), u64::try_from(kurai.place())) {
+            (Ok(n), Ok(place)) => (n / place % kurai.limit() as u64) as u16,
+            _ => (self.値 / kurai.place() % kurai.limit() as u128) as u16,
+        }
+    }
+
+    /// 位の値を書き換える
+    ///
+    /// 値が四桁のまとまりでは10000以上、分・厘・毛では10以上の場合は`OutOfRange`を返す。
+    pub fn set(&mut self, kurai: Kurai, value: u16) -> Result<(), KansujiError> {
+        if value >= kurai.limit() {
+            return Err(KansujiError::OutOfRange);
+        }
+        let place = kurai.place();
+        self.値 = self.値 - self.get(kurai) as u128 * place + value as u128 * place;
+        Ok(())
+    }
+
+    /// 位とその値の組を大きい位から順に返す
+    pub fn groups(&self) -> impl Iterator<Item = (Kurai, u16)> + '_ {
+        Kurai::ALL
+            .iter()
+            .map(move |kurai| (*kurai, self.get(*kurai)))
+    }
+
+    /// 位ごとに値を指定して漢数字を作る
+    pub fn builder() -> KansujiBuilder {
+        KansujiBuilder::default()
+    }
+}
+
+#[test]
+fn check_kurai_1() {
+    let kansuji = Kansuji::try_from("千二百三十四垓五万六千七十八三分五毛").unwrap();
+    assert_eq!(kansuji.get(Kurai::垓), 1234);
+    assert_eq!(kansuji.get(Kurai::億), 0);
+    assert_eq!(kansuji.get(Kurai::万), 5);
+    assert_eq!(kansuji.get(Kurai::一), 6078);
+    assert_eq!(kansuji.get(Kurai::分), 3);
+    assert_eq!(kansuji.get(Kurai::厘), 0);
+    assert_eq!(kansuji.get(Kurai::毛), 5);
+}
+
+#[test]
+fn check_kurai_2() {
+    use super::{KansujiBuffer, KansujiFormat};
+
+    let mut kansuji = Kansuji::from(1_0000_u32);
+    assert_eq!(kansuji.set(Kurai::億, 250), Ok(()));
+    assert_eq!(kansuji.set(Kurai::分, 7), Ok(()));
+    let mut buffer = KansujiBuffer::new();
+    assert_eq!(
+        buffer.format(kansuji, &KansujiFormat::default()),
+        "二百五十億一万七分"
+    );
+    assert_eq!(kansuji.set(Kurai::一, 10000), Err(KansujiError::OutOfRange));
+    assert_eq!(kansuji.set(Kurai::厘, 10), Err(KansujiError::OutOfRange));
+    let groups = kansuji.groups().filter(|(_, n)| *n != 0);
+    assert!(groups.eq([(Kurai::億, 250), (Kurai::万, 1), (Kurai::分, 7)]));
+}
+
+/// 位ごとに値を指定して[`Kansuji`]を作る
+///
+/// 値の範囲は[`build`](KansujiBuilder::build)で検査する。
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct KansujiBuilder {
+    値: [u16; 9],
+}
+
+impl KansujiBuilder {
+    /// すべての位が零のビルダーを作る
+    pub fn new() -> Self {
+        KansujiBuilder::default()
+    }
+
+    /// 位の値を指定する
+    pub fn set(mut self, kurai: Kurai, value: u16) -> Self {
+        self.値[kurai as usize] = value;
+        self
+    }
+
+    /// 漢数字を作る
+    ///
+    /// 範囲を超える値を持つ位がある場合は`OutOfRange`を返す。
+    pub fn build(&self) -> Result<Kansuji, KansujiError> {
+        let mut kansuji = Kansuji::default();
+        for (kurai, value) in Kurai::ALL.iter().zip(self.値.iter()) {
+            kansuji.set(*kurai, *value)?;
+        }
+        Ok(kansuji)
+    }
+}
+
+#[test]
+fn check_kansuji_builder_1() {
+    let kansuji = KansujiBuilder::new()
+        .set(Kurai::兆, 12)
+        .set(Kurai::一, 3400)
+        .set(Kurai::厘, 5)
+        .build()
+        .unwrap();
+    assert_eq!(u128::from(kansuji), 12_0000_0000_3400);
+    assert_eq!(kansuji.get(Kurai::厘), 5);
+    assert_eq!(
+        Kansuji::builder().set(Kurai::万, 10000).build(),
+        Err(KansujiError::OutOfRange)
+    );
+    assert_eq!(
+        Kansuji::builder().set(Kurai::毛, 10).build(),
+        Err(KansujiError::OutOfRange)
+    );
+    assert_eq!(Kansuji::builder().build(), Ok(Kansuji::default()));
+}
@@ -0,0 +1,224 @@
+//! 漢数字を含む文字列を文字ごとに分類する字句解析器
+//!
+//! 大字（壱・萬など）や全角の算用数字のような異体の表記は、同じ種類と値のトークンになる。
+//!
+//! ```
+//! use kansuji::lexer::{Lexer, TokenKind};
+//!
+//! let kinds: Vec<TokenKind> = Lexer::new("壱萬3千").map(|t| t.kind()).collect();
+//! assert_eq!(
+//!     kinds,
+//!     vec![
+//!         TokenKind::Digit(1),
+//!         TokenKind::MyriadUnit(1),
+//!         TokenKind::Digit(3),
+//!         TokenKind::SmallUnit(1000),
+//!     ]
+//! );
+//!
+//! let normalized: String = Lexer::new("▲壱萬").map(|t| t.normalized()).collect();
+//! assert_eq!(normalized, "-一万");
+//! ```
+
+use super::arabic_digit;
+use core::ops::Range;
+use core::str::CharIndices;
+
+/// トークンの種類
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum TokenKind {
+    /// 〇〜九・零・大字・算用数字（値はその数字）
+    Digit(u8),
+    /// 十・百・千（値は10・100・1000）
+    SmallUnit(u16),
+    /// 万・億・兆・京・垓（値は万を1とした四桁ごとの位）
+    MyriadUnit(u8),
+    /// 分・厘・毛（値は小数点以下の桁）
+    FractionUnit(u8),
+    /// 桁区切り（`,`）と小数点（`.`）
+    ///
+    /// 中黒（`・`）は`二・三日`のような列挙に使うため、小数点とせず`Other`とする。
+    Separator(char),
+    /// 符号（`+`・`-`）
+    ///
+    /// 会計で負の数を表す`▲`・`△`も`-`とする。
+    Sign(char),
+    /// それ以外の文字
+    Other(char),
+}
+
+impl TokenKind {
+    /// 文字を分類する
+    // 漢数字を読むときに一文字ごとに呼ぶため、常にインライン化する
+    #[inline(always)]
+    pub fn classify(c: char) -> Self {
+        if let Some(d) = arabic_digit(c) {
+            return TokenKind::Digit(d);
+        }
+        match c {
+            '〇' | '零' => TokenKind::Digit(0),
+            '一' | '壱' | '壹' | '弌' => TokenKind::Digit(1),
+            '二' | '弐' | '貳' | '貮' | '弍' => TokenKind::Digit(2),
+            '三' | '参' | '參' | '弎' => TokenKind::Digit(3),
+            '四' | '肆' => TokenKind::Digit(4),
+            '五' | '伍' => TokenKind::Digit(5),
+            '六' | '陸' => TokenKind::Digit(6),
+            '七' | '漆' | '柒' => TokenKind::Digit(7),
+            '八' | '捌' => TokenKind::Digit(8),
+            '九' | '玖' => TokenKind::Digit(9),
+            '十' | '拾' => TokenKind::SmallUnit(10),
+            '百' | '佰' | '陌' => TokenKind::SmallUnit(100),
+            '千' | '阡' | '仟' => TokenKind::SmallUnit(1000),
+            '万' | '萬' => TokenKind::MyriadUnit(1),
+            '億' => TokenKind::MyriadUnit(2),
+            '兆' => TokenKind::MyriadUnit(3),
+            '京' => TokenKind::MyriadUnit(4),
+            '垓' => TokenKind::MyriadUnit(5),
+            '分' => TokenKind::FractionUnit(1),
+            '厘' => TokenKind::FractionUnit(2),
+            '毛' => TokenKind::FractionUnit(3),
+            ',' | '，' => TokenKind::Separator(','),
+            '.' | '．' => TokenKind::Separator('.'),
+            '+' | '＋' => TokenKind::Sign('+'),
+            '-' | '－' | '−' | '▲' | '△' => TokenKind::Sign('-'),
+            c => TokenKind::Other(c),
+        }
+    }
+
+    /// 漢数字の数字または位かどうかを返す
+    pub fn is_numeral(self) -> bool {
+        matches!(
+            self,
+            TokenKind::Digit(_)
+                | TokenKind::SmallUnit(_)
+                | TokenKind::MyriadUnit(_)
+                | TokenKind::FractionUnit(_)
+        )
+    }
+
+    /// 異体を統一した表記を返す
+    ///
+    /// 数字は漢数字（〇・一〜九）、位は常用の漢字とする。
+    pub fn normalized(self) -> char {
+        match self {
+            TokenKind::Digit(d) => {
+                ['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'][d as usize]
+            }
+            TokenKind::SmallUnit(10) => '十',
+            TokenKind::SmallUnit(100) => '百',
+            TokenKind::SmallUnit(_) => '千',
+            TokenKind::MyriadUnit(1) => '万',
+            TokenKind::MyriadUnit(2) => '億',
+            TokenKind::MyriadUnit(3) => '兆',
+            TokenKind::MyriadUnit(4) => '京',
+            TokenKind::MyriadUnit(_) => '垓',
+            TokenKind::FractionUnit(1) => '分',
+            TokenKind::FractionUnit(2) => '厘',
+            TokenKind::FractionUnit(_) => '毛',
+            TokenKind::Separator(c) | TokenKind::Sign(c) | TokenKind::Other(c) => c,
+        }
+    }
+}
+
+#[test]
+fn check_classify_1() {
+    let v = [
+        ('五', TokenKind::Digit(5)),
+        ('伍', TokenKind::Digit(5)),
+        ('5', TokenKind::Digit(5)),
+        ('５', TokenKind::Digit(5)),
+        ('〇', TokenKind::Digit(0)),
+        ('拾', TokenKind::SmallUnit(10)),
+        ('仟', TokenKind::SmallUnit(1000)),
+        ('萬', TokenKind::MyriadUnit(1)),
+        ('垓', TokenKind::MyriadUnit(5)),
+        ('厘', TokenKind::FractionUnit(2)),
+        ('，', TokenKind::Separator(',')),
+        ('．', TokenKind::Separator('.')),
+        ('・', TokenKind::Other('・')),
+        ('▲', TokenKind::Sign('-')),
+        ('＋', TokenKind::Sign('+')),
+        ('円', TokenKind::Other('円')),
+    ];
+    for (c, kind) in v.iter() {
+        assert_eq!(TokenKind::classify(*c), *kind);
+    }
+}
+
+/// 元の文字列での位置を持つトークン
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    span: Range<usize>,
+}
+
+impl<'a> Token<'a> {
+    /// トークンの種類を返す
+    pub fn kind(&self) -> TokenKind {
+        self.kind
+    }
+
+    /// 元の表記を返す
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// 元の文字列でのバイト位置を返す
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// 異体を統一した表記を返す
+    pub fn normalized(&self) -> char {
+        self.kind.normalized()
+    }
+}
+
+/// 文字列を先頭から一文字ずつトークンに分ける
+#[derive(Clone, Debug)]
+pub struct Lexer<'a> {
+    s: &'a str,
+    chars: CharIndices<'a>,
+}
+
+impl<'a> Lexer<'a> {
+    /// 文字列の字句解析器を作る
+    pub fn new(s: &'a str) -> Self {
+        Lexer {
+            s,
+            chars: s.char_indices(),
+        }
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, c) = self.chars.next()?;
+        let span = i..i + c.len_utf8();
+        Some(Token {
+            kind: TokenKind::classify(c),
+            text: &self.s[span.clone()],
+            span,
+        })
+    }
+}
+
+#[test]
+fn check_lexer_1() {
+    assert_eq!(Lexer::new("約3万円").count(), 4);
+    let mut tokens = Lexer::new("約3万円");
+    assert_eq!(
+        tokens.next().map(|t| t.kind()),
+        Some(TokenKind::Other('約'))
+    );
+    let token = tokens.next().unwrap();
+    assert_eq!(token.kind(), TokenKind::Digit(3));
+    assert_eq!(token.span(), 3..4);
+    let token = tokens.next().unwrap();
+    assert_eq!(token.text(), "万");
+    assert_eq!(token.span(), 4..7);
+    assert_eq!(tokens.next().map(|t| t.normalized()), Some('円'));
+    assert_eq!(Lexer::new("").next(), None);
+}
@@ -4,7 +4,9 @@
 //! サポートする漢数字の桁の範囲は垓(10^20)から毛(10^-3)までとする
 //! (<https://homepage45.net/unit/sub.htm>)
 //!
-//! なお、大字をどこまでサポートするかは今後決めるものとする。
+//! 大字（壱・弐・参・拾・萬など）や算用数字が混ざったもの（`3万5千`など）も読み取ることができる。
+//!
+//! 大字での出力は`KansujiFormat`で指定する。
 //!
 //! # 使い方
 //!
@@ -21,6 +23,17 @@
 //! let kansuji2 = Kansuji::from(n);
 //! assert_eq!(s.to_string(), kansuji2.to_string());
 //! ```
+//!
+//! # features
+//!
+//! - `std`（既定）: `std::error::Error`の実装と`std::io::Write`への書き込みを有効にする。
+//! - `alloc`: `String`を返すAPIと、漢数字以外の語を含む表現（助数詞・日付・時刻など）を扱うモジュールを有効にする。
+//! - `clap`: コマンドライン引数を漢数字からも読むための`clap`の`ValueParser`を`clap`モジュールで提供する。`std`も有効にする必要がある。
+//! - `ffi`: C言語から使うための`extern "C"`の関数を`ffi`モジュールで提供する。`std`も有効になる。
+//! - `serde`: [`Kansuji`]の`Serialize`・`Deserialize`の実装と、表現を選ぶための`serde`モジュールを有効にする。
+//! - `wasm`: WebAssemblyから使うための関数を`wasm-bindgen`で`wasm`モジュールに公開する。`std`も有効になる。
+//!
+//! `std`と`alloc`をどちらも無効にした場合は`no_std`で動作し、漢数字の解析と[`KansujiBuffer`]などの`fmt::Write`への書き込みが使える。
 //!---
 
 //! [The MIT License](https://github.com/puripuri2100/kansuji-rs/blob/master/LICENSE)
@@ -28,9 +41,85 @@
 //! Copyright (c) 2024 Naoki Kaneko (a.k.a. "puripuri2100")
 //!
 
-use std::convert::{From, TryFrom};
-use std::string::String;
-use thiserror::Error;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(all(test, feature = "alloc"))]
+use alloc::{string::ToString, vec};
+use core::convert::{From, TryFrom};
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+pub mod approx;
+pub mod bairitsu;
+#[cfg(feature = "alloc")]
+pub mod banchi;
+pub mod buai;
+pub mod buffer;
+#[cfg(all(feature = "clap", feature = "std"))]
+pub mod clap;
+#[cfg(feature = "alloc")]
+pub mod cst;
+#[cfg(feature = "alloc")]
+pub mod dates;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "alloc")]
+pub mod find;
+#[cfg(feature = "alloc")]
+pub mod hanni;
+#[cfg(feature = "alloc")]
+pub mod josuushi;
+pub mod kurai;
+pub mod lexer;
+#[cfg(feature = "alloc")]
+pub mod ordinal;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "alloc")]
+pub mod shakkan;
+#[cfg(feature = "alloc")]
+pub mod time;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "alloc")]
+pub mod yen;
+#[cfg(feature = "alloc")]
+pub mod yomi;
+
+#[cfg(feature = "alloc")]
+pub use approx::Approx;
+pub use bairitsu::Bairitsu;
+#[cfg(feature = "alloc")]
+pub use banchi::Banchi;
+pub use buai::Buai;
+pub use buffer::KansujiBuffer;
+#[cfg(feature = "alloc")]
+pub use cst::{CstGroup, CstTerm, CstToken, KansujiCst};
+#[cfg(feature = "alloc")]
+pub use dates::{Gengo, Hizuke};
+#[cfg(feature = "alloc")]
+pub use find::{find_iter, replace_all, KansujiMatch, Matches};
+#[cfg(feature = "alloc")]
+pub use hanni::Hanni;
+#[cfg(feature = "alloc")]
+pub use josuushi::{Josuushi, JosuushiTable, Suuryou};
+pub use kurai::{KansujiBuilder, Kurai};
+pub use lexer::{Lexer, Token, TokenKind};
+#[cfg(feature = "alloc")]
+pub use ordinal::{Ordinal, OrdinalKind, OrdinalPath};
+#[cfg(feature = "alloc")]
+pub use shakkan::{Shakkan, ShakkanShurui};
+#[cfg(feature = "alloc")]
+pub use time::{Gozengogo, Jikoku, Kikan};
+#[cfg(feature = "alloc")]
+pub use yen::Yen;
+#[cfg(feature = "alloc")]
+pub use yomi::Onbin;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum KansujiField {
@@ -78,33 +167,48 @@ impl KansujiField {
         }
     }
 
-    fn to_str(self) -> String {
+    fn to_str(self) -> &'static str {
+        match self {
+            KansujiField::零 => "",
+            KansujiField::一 => "",
+            KansujiField::二 => "二",
+            KansujiField::三 => "三",
+            KansujiField::四 => "四",
+            KansujiField::五 => "五",
+            KansujiField::六 => "六",
+            KansujiField::七 => "七",
+            KansujiField::八 => "八",
+            KansujiField::九 => "九",
+        }
+    }
+
+    fn to_str2(self) -> &'static str {
         match self {
-            KansujiField::零 => String::new(),
-            KansujiField::一 => String::new(),
-            KansujiField::二 => "二".to_string(),
-            KansujiField::三 => "三".to_string(),
-            KansujiField::四 => "四".to_string(),
-            KansujiField::五 => "五".to_string(),
-            KansujiField::六 => "六".to_string(),
-            KansujiField::七 => "七".to_string(),
-            KansujiField::八 => "八".to_string(),
-            KansujiField::九 => "九".to_string(),
+            KansujiField::零 => "",
+            KansujiField::一 => "一",
+            KansujiField::二 => "二",
+            KansujiField::三 => "三",
+            KansujiField::四 => "四",
+            KansujiField::五 => "五",
+            KansujiField::六 => "六",
+            KansujiField::七 => "七",
+            KansujiField::八 => "八",
+            KansujiField::九 => "九",
         }
     }
 
-    fn to_str2(self) -> String {
+    fn to_daiji(self) -> &'static str {
         match self {
-            KansujiField::零 => String::new(),
-            KansujiField::一 => "一".to_string(),
-            KansujiField::二 => "二".to_string(),
-            KansujiField::三 => "三".to_string(),
-            KansujiField::四 => "四".to_string(),
-            KansujiField::五 => "五".to_string(),
-            KansujiField::六 => "六".to_string(),
-            KansujiField::七 => "七".to_string(),
-            KansujiField::八 => "八".to_string(),
-            KansujiField::九 => "九".to_string(),
+            KansujiField::零 => "",
+            KansujiField::一 => "壱",
+            KansujiField::二 => "弐",
+            KansujiField::三 => "参",
+            KansujiField::四 => "肆",
+            KansujiField::五 => "伍",
+            KansujiField::六 => "陸",
+            KansujiField::七 => "漆",
+            KansujiField::八 => "捌",
+            KansujiField::九 => "玖",
         }
     }
 }
@@ -124,6 +228,9 @@ impl KansujiKeta {
             && self.十 == KansujiField::零
             && self.一 == KansujiField::零
     }
+    fn is_one_digit(self) -> bool {
+        self.千 == KansujiField::零 && self.百 == KansujiField::零 && self.十 == KansujiField::零
+    }
     fn is_one(self) -> bool {
         self.千 == KansujiField::零
             && self.百 == KansujiField::零
@@ -132,24 +239,43 @@ impl KansujiKeta {
     }
 }
 
-impl ToString for KansujiKeta {
-    fn to_string(&self) -> String {
-        let mut s = String::new();
-        if self.千 != KansujiField::零 {
-            s.push_str(&format!("{}千", self.千.to_str()))
+impl KansujiKeta {
+    fn write_with<W: fmt::Write>(self, f: &mut W, format: &KansujiFormat) -> fmt::Result {
+        if format.daiji {
+            for (field, unit) in [(self.千, "阡"), (self.百, "佰"), (self.十, "拾")] {
+                if field != KansujiField::零 {
+                    f.write_str(field.to_daiji())?;
+                    f.write_str(unit)?;
+                }
+            }
+            return f.write_str(self.一.to_daiji());
         }
-        if self.百 != KansujiField::零 {
-            s.push_str(&format!("{}百", self.百.to_str()))
+        if self.千 != KansujiField::零 {
+            if format.explicit_one {
+                f.write_str(self.千.to_str2())?;
+            } else {
+                f.write_str(self.千.to_str())?;
+            }
+            f.write_str("千")?;
         }
-        if self.十 != KansujiField::零 {
-            s.push_str(&format!("{}十", self.十.to_str()))
+        for (field, unit) in [(self.百, "百"), (self.十, "十")] {
+            if field != KansujiField::零 {
+                f.write_str(field.to_str())?;
+                f.write_str(unit)?;
+            }
         }
-        s.push_str(&self.一.to_str2());
-        s
+        f.write_str(self.一.to_str2())
+    }
+}
+
+impl fmt::Display for KansujiKeta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_with(f, &KansujiFormat::default())
     }
 }
 
 impl From<KansujiKeta> for usize {
+    #[inline]
     fn from(value: KansujiKeta) -> Self {
         let mut n = value.一.to_int() as usize;
         n += value.十.to_int() as usize * 10;
@@ -186,47 +312,51 @@ impl Default for KansujiKeta {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// 漢数字
+///
+/// 値は毛（千分の一）を単位とした整数で持つ。位ごとの値は[`Kansuji::get`]で得られる。
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
 pub struct Kansuji {
-    垓: KansujiKeta,
-    京: KansujiKeta,
-    兆: KansujiKeta,
-    億: KansujiKeta,
-    万: KansujiKeta,
-    一: KansujiKeta,
-    分: KansujiField,
-    厘: KansujiField,
-    毛: KansujiField,
-}
-
-impl Default for Kansuji {
-    fn default() -> Self {
-        Kansuji {
-            垓: KansujiKeta::default(),
-            京: KansujiKeta::default(),
-            兆: KansujiKeta::default(),
-            億: KansujiKeta::default(),
-            万: KansujiKeta::default(),
-            一: KansujiKeta::default(),
-            分: KansujiField::零,
-            厘: KansujiField::零,
-            毛: KansujiField::零,
-        }
-    }
+    値: u128,
 }
 
-#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum KansujiError {
-    #[error("parse error")]
     ParseError,
-    #[error("unexpected char: {0}")]
     UnexpectedChar(char),
-    #[error("unexpected end")]
     UnexpectedEnd,
-    #[error("too large")]
     TooLarge,
+    OutOfRange,
+}
+
+impl fmt::Display for KansujiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KansujiError::ParseError => write!(f, "parse error"),
+            KansujiError::UnexpectedChar(c) => write!(f, "unexpected char: {}", c),
+            KansujiError::UnexpectedEnd => write!(f, "unexpected end"),
+            KansujiError::TooLarge => write!(f, "too large"),
+            KansujiError::OutOfRange => write!(f, "out of range"),
+        }
+    }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for KansujiError {}
+
+#[cfg(feature = "std")]
+#[test]
+fn check_kansuji_error_1() {
+    assert_eq!(KansujiError::ParseError.to_string(), "parse error");
+    assert_eq!(
+        KansujiError::UnexpectedChar('円').to_string(),
+        "unexpected char: 円"
+    );
+    let e: Box<dyn std::error::Error> = Box::new(KansujiError::TooLarge);
+    assert_eq!(e.to_string(), "too large");
+}
+
+#[cfg(feature = "alloc")]
 impl TryFrom<String> for Kansuji {
     type Error = KansujiError;
     fn try_from(value: String) -> Result<Self, Self::Error> {
@@ -235,6 +365,7 @@ impl TryFrom<String> for Kansuji {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl TryFrom<&String> for Kansuji {
     type Error = KansujiError;
     fn try_from(value: &String) -> Result<Self, Self::Error> {
@@ -251,144 +382,306 @@ impl TryFrom<&str> for Kansuji {
     }
 }
 
-fn parse_kansuji(chars: std::str::Chars) -> Result<Kansuji, KansujiError> {
-    let mut chars = chars.peekable();
-    let mut kansuji = Kansuji::default();
+impl Kansuji {
+    /// UTF-8のバイト列を漢数字として読む
+    ///
+    /// UTF-8として正しくない場合は`ParseError`を返す。
+    pub fn parse_bytes(bytes: &[u8]) -> Result<Self, KansujiError> {
+        let s = core::str::from_utf8(bytes).map_err(|_| KansujiError::ParseError)?;
+        parse_kansuji(s.chars())
+    }
+
+    /// 文字列の先頭にある漢数字を読み、その値と読んだバイト数を返す
+    ///
+    /// 数字と位（[`TokenKind::is_numeral`]）が続く部分を読む。
+    /// 先頭が漢数字でない場合や、続く部分が漢数字として正しくない場合はエラーを返す。
+    /// `分かる`のように数のない位で始まる場合もエラーとする。
+    ///
+    /// ```
+    /// use kansuji::Kansuji;
+    ///
+    /// let (kansuji, len) = Kansuji::parse_prefix("三百二十円です").unwrap();
+    /// assert_eq!(u128::from(kansuji), 320);
+    /// assert_eq!(len, "三百二十".len());
+    /// ```
+    pub fn parse_prefix(s: &str) -> Result<(Self, usize), KansujiError> {
+        let len = s
+            .char_indices()
+            .find(|(_, c)| !TokenKind::classify(*c).is_numeral())
+            .map_or(s.len(), |(i, _)| i);
+        if len == 0 {
+            return Err(s
+                .chars()
+                .next()
+                .map_or(KansujiError::UnexpectedEnd, KansujiError::UnexpectedChar));
+        }
+        let kansuji = parse_kansuji(s[..len].chars())?;
+        Ok((kansuji, len))
+    }
+
+    /// `320`や`1.5`のような十進の文字列を読む
+    ///
+    /// 小数点以下は毛（三桁）までとする。
+    ///
+    /// ```
+    /// use kansuji::Kansuji;
+    ///
+    /// let kansuji = Kansuji::parse_decimal("12345.6").unwrap();
+    /// assert_eq!(kansuji.to_string(), "一万二千三百四十五六分");
+    /// ```
+    pub fn parse_decimal(s: &str) -> Result<Self, KansujiError> {
+        parse_decimal_at(s).map_err(|(e, _)| e)
+    }
+
+    /// 小数から作る
+    ///
+    /// `From<f64>`と異なり、負の数と`NaN`は`OutOfRange`、無限大と10の24乗以上の数は`TooLarge`とする。
+    ///
+    /// ```
+    /// use kansuji::{Kansuji, KansujiError};
+    ///
+    /// assert_eq!(Kansuji::try_from_f64(1.5), Ok(Kansuji::from(1.5)));
+    /// assert_eq!(Kansuji::try_from_f64(-1.0), Err(KansujiError::OutOfRange));
+    /// assert_eq!(Kansuji::try_from_f64(1e24), Err(KansujiError::TooLarge));
+    /// ```
+    pub fn try_from_f64(value: f64) -> Result<Self, KansujiError> {
+        if value.is_nan() || value < 0.0 {
+            return Err(KansujiError::OutOfRange);
+        }
+        if value >= KANSUJI_LIMIT as f64 {
+            return Err(KansujiError::TooLarge);
+        }
+        Ok(Kansuji::from(value))
+    }
+}
+
+/// 十進の文字列を読み、エラーの場合はその原因となった位置（バイト）も返す
+fn parse_decimal_at(s: &str) -> Result<Kansuji, (KansujiError, usize)> {
+    let dot = s.find('.');
+    let (integer, fraction) = match dot {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => (s, ""),
+    };
+    if s.is_empty() || s.ends_with('.') {
+        return Err((KansujiError::UnexpectedEnd, s.len()));
+    }
+    if integer.is_empty() || fraction.len() > 3 {
+        return Err((KansujiError::ParseError, 0));
+    }
+    if let Some((i, c)) = s
+        .char_indices()
+        .find(|&(i, c)| Some(i) != dot && !c.is_ascii_digit())
+    {
+        return Err((KansujiError::UnexpectedChar(c), i));
+    }
+    let mut mou: u128 = 0;
+    for b in integer.bytes() {
+        mou = mou
+            .checked_mul(10)
+            .and_then(|n| n.checked_add((b - b'0') as u128))
+            .filter(|n| *n < KANSUJI_LIMIT)
+            .ok_or((KansujiError::TooLarge, 0))?;
+    }
+    mou *= 1000;
+    for (i, b) in fraction.bytes().enumerate() {
+        mou += (b - b'0') as u128 * 10_u128.pow(2 - i as u32);
+    }
+    Ok(Kansuji::from_mou(mou))
+}
+
+/// 漢数字または`320`・`1.5`のような十進の文字列を読む
+#[cfg(any(feature = "alloc", feature = "serde"))]
+fn parse_kansuji_or_decimal(s: &str) -> Result<Kansuji, KansujiError> {
+    parse_kansuji_or_decimal_at(s).map_err(|(e, _)| e)
+}
+
+/// 漢数字または十進の文字列を読み、エラーの場合はその原因となった位置（バイト）も返す
+///
+/// 位置は`UnexpectedChar`ではその文字の位置、`UnexpectedEnd`では文字列の長さとする。
+#[cfg(any(feature = "alloc", feature = "serde"))]
+fn parse_kansuji_or_decimal_at(s: &str) -> Result<Kansuji, (KansujiError, usize)> {
+    if s.is_empty() {
+        return Err((KansujiError::UnexpectedEnd, 0));
+    }
+    if s.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+        return parse_decimal_at(s);
+    }
+    parse_kansuji_at(s)
+}
+
+#[test]
+fn check_parse_decimal_1() {
+    assert_eq!(Kansuji::parse_decimal("320"), Ok(Kansuji::from(320_u16)));
+    assert_eq!(Kansuji::parse_decimal("1.5"), Ok(Kansuji::from(1.5)));
+    assert_eq!(Kansuji::parse_decimal("0.005"), Ok(Kansuji::from_mou(5)));
+    assert_eq!(
+        Kansuji::parse_decimal("1.2345"),
+        Err(KansujiError::ParseError)
+    );
+    assert_eq!(Kansuji::parse_decimal(".5"), Err(KansujiError::ParseError));
+    assert_eq!(
+        Kansuji::parse_decimal("1."),
+        Err(KansujiError::UnexpectedEnd)
+    );
+    assert_eq!(
+        Kansuji::parse_decimal("1.2.3"),
+        Err(KansujiError::UnexpectedChar('.'))
+    );
+    assert_eq!(
+        Kansuji::parse_decimal("三百"),
+        Err(KansujiError::UnexpectedChar('三'))
+    );
+    assert_eq!(
+        Kansuji::parse_decimal("1000000000000000000000000"),
+        Err(KansujiError::TooLarge)
+    );
+}
+
+#[test]
+fn check_try_from_f64_1() {
+    assert_eq!(Kansuji::try_from_f64(0.0), Ok(Kansuji::default()));
+    assert_eq!(Kansuji::try_from_f64(12.345), Ok(Kansuji::from_mou(12345)));
+    assert_eq!(
+        Kansuji::try_from_f64(f64::NAN),
+        Err(KansujiError::OutOfRange)
+    );
+    assert_eq!(
+        Kansuji::try_from_f64(f64::INFINITY),
+        Err(KansujiError::TooLarge)
+    );
+    assert_eq!(
+        Kansuji::try_from_f64(f64::NEG_INFINITY),
+        Err(KansujiError::OutOfRange)
+    );
+}
+
+#[test]
+fn check_parse_bytes_1() {
+    assert_eq!(
+        Kansuji::parse_bytes("三百二十".as_bytes()),
+        Ok(Kansuji::from(320_u16))
+    );
+    assert_eq!(
+        Kansuji::parse_bytes("一二分".as_bytes()),
+        Ok(Kansuji::from(1.2))
+    );
+    assert_eq!(
+        Kansuji::parse_bytes(&"三百".as_bytes()[..4]),
+        Err(KansujiError::ParseError)
+    );
+}
+
+#[test]
+fn check_parse_prefix_1() {
+    let s = "価格は三万五千円、税込み";
+    let i = s.find('三').unwrap();
+    let (kansuji, len) = Kansuji::parse_prefix(&s[i..]).unwrap();
+    assert_eq!(u128::from(kansuji), 35000);
+    assert_eq!(&s[i + len..], "円、税込み");
+    assert_eq!(Kansuji::parse_prefix("一割"), Ok((Kansuji::from(1_u8), 3)));
+    assert_eq!(
+        Kansuji::parse_prefix("12万"),
+        Ok((Kansuji::from(120000_u32), 5))
+    );
+    assert_eq!(
+        Kansuji::parse_prefix("五分五厘"),
+        Ok((Kansuji::from(0.55), 12))
+    );
+    assert_eq!(
+        Kansuji::parse_prefix("円"),
+        Err(KansujiError::UnexpectedChar('円'))
+    );
+    assert_eq!(Kansuji::parse_prefix(""), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(
+        Kansuji::parse_prefix("12345円"),
+        Err(KansujiError::UnexpectedChar('5'))
+    );
+}
+
+#[test]
+fn check_parse_prefix_2() {
+    assert_eq!(
+        Kansuji::parse_prefix("分かる"),
+        Err(KansujiError::UnexpectedChar('分'))
+    );
+    assert_eq!(
+        Kansuji::parse_prefix("万が一"),
+        Err(KansujiError::UnexpectedChar('万'))
+    );
+    assert_eq!(
+        Kansuji::parse_prefix("億万長者"),
+        Err(KansujiError::UnexpectedChar('億'))
+    );
+}
+
+fn parse_kansuji(chars: core::str::Chars) -> Result<Kansuji, KansujiError> {
+    parse_kansuji_at(chars.as_str()).map_err(|(e, _)| e)
+}
+
+/// 漢数字を読み、エラーの場合はその原因となった位置（バイト）も返す
+fn parse_kansuji_at(s: &str) -> Result<Kansuji, (KansujiError, usize)> {
+    // 残りの文字から位置を求める（エラーの場合にだけ呼ぶ）
+    let at = |chars: &core::iter::Peekable<core::str::Chars>| {
+        s.len() - chars.clone().map(char::len_utf8).sum::<usize>()
+    };
+    let mut chars = s.chars().peekable();
+    let mut mou: u128 = 0;
     let mut keta = 6_i8;
+    let mut trailing: Option<core::iter::Peekable<core::str::Chars>> = None;
     loop {
-        let kansuji_keta = parse_keta(&mut chars)?;
-        if let Some(c) = chars.peek() {
-            match c {
-                '垓' => {
-                    if keta > 5 {
-                        kansuji = Kansuji {
-                            垓: kansuji_keta,
-                            ..kansuji
-                        };
-                        chars.next();
-                        keta = 5;
-                    } else {
-                        return Err(KansujiError::UnexpectedChar(*c));
-                    }
-                }
-                '京' => {
-                    if keta > 4 {
-                        kansuji = Kansuji {
-                            京: kansuji_keta,
-                            ..kansuji
-                        };
-                        chars.next();
-                        keta = 4;
-                    } else {
-                        return Err(KansujiError::UnexpectedChar(*c));
-                    }
-                }
-                '兆' => {
-                    if keta > 3 {
-                        kansuji = Kansuji {
-                            兆: kansuji_keta,
-                            ..kansuji
-                        };
-                        chars.next();
-                        keta = 3;
-                    } else {
-                        return Err(KansujiError::UnexpectedChar(*c));
-                    }
-                }
-                '億' => {
-                    if keta > 2 {
-                        kansuji = Kansuji {
-                            億: kansuji_keta,
-                            ..kansuji
-                        };
-                        chars.next();
-                        keta = 2;
-                    } else {
-                        return Err(KansujiError::UnexpectedChar(*c));
-                    }
-                }
-                '万' => {
-                    if keta > 1 {
-                        kansuji = Kansuji {
-                            万: kansuji_keta,
-                            ..kansuji
-                        };
-                        chars.next();
-                        keta = 1;
-                    } else {
-                        return Err(KansujiError::UnexpectedChar(*c));
-                    }
-                }
-                '分' => {
-                    if keta > -1
-                        && kansuji_keta.百 == KansujiField::零
-                        && kansuji_keta.千 == KansujiField::零
-                        && kansuji_keta.十 == KansujiField::零
-                    {
-                        kansuji = Kansuji {
-                            分: kansuji_keta.一,
-                            ..kansuji
-                        };
-                        chars.next();
-                        keta = -1;
-                    } else {
-                        return Err(KansujiError::UnexpectedChar(*c));
-                    }
-                }
-                '厘' => {
-                    if keta > -2
-                        && kansuji_keta.百 == KansujiField::零
-                        && kansuji_keta.千 == KansujiField::零
-                        && kansuji_keta.十 == KansujiField::零
-                    {
-                        kansuji = Kansuji {
-                            厘: kansuji_keta.一,
-                            ..kansuji
-                        };
-                        chars.next();
-                        keta = -2;
-                    } else {
-                        return Err(KansujiError::UnexpectedChar(*c));
-                    }
-                }
-                '毛' => {
-                    if keta > -3
-                        && kansuji_keta.百 == KansujiField::零
-                        && kansuji_keta.千 == KansujiField::零
-                        && kansuji_keta.十 == KansujiField::零
-                    {
-                        kansuji = Kansuji {
-                            毛: kansuji_keta.一,
-                            ..kansuji
-                        };
-                        chars.next();
-                        break;
-                    } else {
-                        return Err(KansujiError::UnexpectedChar(*c));
-                    }
+        let mut group_start = chars.clone();
+        let group = parse_keta(&mut chars).map_err(|e| (e, at(&chars)))?;
+        // 零は数全体が零の場合にだけ使える
+        if let (Some(0), Some(&c)) = (group, group_start.peek()) {
+            if keta < 6 || chars.peek().is_some() {
+                return Err((KansujiError::UnexpectedChar(c), at(&group_start)));
+            }
+        }
+        let n = group.unwrap_or(0);
+        let c = match chars.peek() {
+            Some(&c) => c,
+            None => {
+                if keta > 0 {
+                    mou += n as u128 * Kurai::一.place();
+                } else if let (0, Some(mut trailing)) = (keta, trailing) {
+                    let c = trailing.peek().copied().unwrap_or_default();
+                    return Err((KansujiError::UnexpectedChar(c), at(&trailing)));
+                } else if n != 0 {
+                    return Err((KansujiError::UnexpectedEnd, s.len()));
                 }
-                _ => {
-                    if keta > 0 {
-                        kansuji = Kansuji {
-                            一: kansuji_keta,
-                            ..kansuji
-                        };
-                        chars.next();
-                        keta = 0;
-                    } else {
-                        break;
-                    }
+                break;
+            }
+        };
+        // 万以上の位は四桁の塊を、分・厘・毛は一桁の数を取る
+        let next = match TokenKind::classify(c) {
+            TokenKind::MyriadUnit(u) => u as i8,
+            TokenKind::FractionUnit(u) if n < 10 => -(u as i8),
+            TokenKind::FractionUnit(_) => {
+                return Err((KansujiError::UnexpectedChar(c), at(&chars)))
+            }
+            _ => {
+                if keta > 0 {
+                    mou += n as u128 * Kurai::一.place();
+                    // 一の位の後に続く数字は、分・厘・毛が続く場合にだけ使える（一二分など）
+                    trailing = Some(chars.clone());
+                    keta = 0;
+                    continue;
                 }
+                return Err((KansujiError::UnexpectedChar(c), at(&chars)));
             }
-        } else {
-            kansuji = Kansuji {
-                一: kansuji_keta,
-                ..kansuji
-            };
+        };
+        // 万・億などや分・厘・毛の前には数がなければならない（「万」や「一億万」は読まない）
+        if keta <= next || group.is_none() {
+            return Err((KansujiError::UnexpectedChar(c), at(&chars)));
+        }
+        mou += n as u128 * Kurai::ALL[(5 - next) as usize].place();
+        chars.next();
+        keta = next;
+        if keta == -3 {
             break;
         }
     }
-    Ok(kansuji)
+    Ok(Kansuji { 値: mou })
 }
 
 #[test]
@@ -397,23 +690,10 @@ fn check_parse_kansuji_1() {
     let kansuji = parse_kansuji(str.chars());
     assert_eq!(
         kansuji,
-        Ok(Kansuji {
-            垓: KansujiKeta::default(),
-            京: KansujiKeta::default(),
-            兆: KansujiKeta::default(),
-            億: KansujiKeta::default(),
-            万: KansujiKeta {
-                百: KansujiField::一,
-                ..KansujiKeta::default()
-            },
-            一: KansujiKeta {
-                一: KansujiField::一,
-                ..KansujiKeta::default()
-            },
-            分: KansujiField::零,
-            厘: KansujiField::零,
-            毛: KansujiField::零,
-        })
+        Kansuji::builder()
+            .set(Kurai::万, 100)
+            .set(Kurai::一, 1)
+            .build()
     )
 }
 
@@ -423,28 +703,11 @@ fn check_parse_kansuji_2() {
     let kansuji = parse_kansuji(str.chars());
     assert_eq!(
         kansuji,
-        Ok(Kansuji {
-            垓: KansujiKeta {
-                百: KansujiField::二,
-                一: KansujiField::五,
-                ..KansujiKeta::default()
-            },
-            京: KansujiKeta::default(),
-            兆: KansujiKeta::default(),
-            億: KansujiKeta::default(),
-            万: KansujiKeta {
-                百: KansujiField::一,
-                ..KansujiKeta::default()
-            },
-            一: KansujiKeta {
-                十: KansujiField::二,
-                一: KansujiField::一,
-                ..KansujiKeta::default()
-            },
-            分: KansujiField::零,
-            厘: KansujiField::零,
-            毛: KansujiField::零,
-        })
+        Kansuji::builder()
+            .set(Kurai::垓, 205)
+            .set(Kurai::万, 100)
+            .set(Kurai::一, 21)
+            .build()
     )
 }
 
@@ -455,177 +718,388 @@ fn check_parse_kansuji_3() {
     assert!(kansuji.is_err())
 }
 
+#[test]
+fn check_parse_kansuji_4() {
+    let str = "七億六千四百九十二万三千三百四十一";
+    let kansuji = parse_kansuji(str.chars());
+    assert_eq!(kansuji, Ok(Kansuji::from(764923341_u128)))
+}
+
+#[test]
+fn check_parse_kansuji_5() {
+    let str = "一二分三厘四毛";
+    let kansuji = parse_kansuji(str.chars());
+    assert_eq!(kansuji, Ok(Kansuji::from(1.234)));
+    let str = "三分";
+    let kansuji = parse_kansuji(str.chars());
+    assert_eq!(kansuji, Ok(Kansuji::from(0.3)));
+}
+
+#[test]
+fn check_parse_kansuji_6() {
+    assert_eq!(
+        parse_kansuji("一二".chars()),
+        Err(KansujiError::UnexpectedChar('二'))
+    );
+    assert_eq!(
+        parse_kansuji("一分二".chars()),
+        Err(KansujiError::UnexpectedEnd)
+    );
+    assert_eq!(
+        parse_kansuji("三割".chars()),
+        Err(KansujiError::UnexpectedChar('割'))
+    );
+}
+
+#[test]
+fn check_parse_kansuji_9() {
+    assert_eq!(parse_kansuji("零".chars()), Ok(Kansuji::default()));
+    assert_eq!(
+        parse_kansuji("三零".chars()),
+        Err(KansujiError::UnexpectedChar('零'))
+    );
+    assert_eq!(
+        parse_kansuji("二十0".chars()),
+        Err(KansujiError::UnexpectedChar('0'))
+    );
+    assert_eq!(
+        parse_kansuji("二十零".chars()),
+        Err(KansujiError::UnexpectedChar('零'))
+    );
+    assert_eq!(
+        parse_kansuji("三万零".chars()),
+        Err(KansujiError::UnexpectedChar('零'))
+    );
+    assert_eq!(
+        parse_kansuji("零万".chars()),
+        Err(KansujiError::UnexpectedChar('零'))
+    );
+    assert_eq!(
+        parse_kansuji("一分零".chars()),
+        Err(KansujiError::UnexpectedChar('零'))
+    );
+}
+
+#[test]
+fn check_parse_kansuji_7() {
+    let str = "参萬伍阡壱百弐拾";
+    let kansuji = parse_kansuji(str.chars());
+    assert_eq!(kansuji, Ok(Kansuji::from(35120_u128)));
+    let str = "壱億";
+    let kansuji = parse_kansuji(str.chars());
+    assert_eq!(kansuji, Ok(Kansuji::from(100000000_u128)));
+}
+
+#[test]
+fn check_parse_kansuji_8() {
+    let str = "3万5千";
+    let kansuji = parse_kansuji(str.chars());
+    assert_eq!(kansuji, Ok(Kansuji::from(35000_u128)));
+    let str = "1234億5678万";
+    let kansuji = parse_kansuji(str.chars());
+    assert_eq!(kansuji, Ok(Kansuji::from(123456780000_u128)));
+    let str = "１２万";
+    let kansuji = parse_kansuji(str.chars());
+    assert_eq!(kansuji, Ok(Kansuji::from(120000_u128)));
+    let str = "12千";
+    let kansuji = parse_kansuji(str.chars());
+    assert_eq!(kansuji, Err(KansujiError::UnexpectedChar('千')));
+    let str = "12345";
+    let kansuji = parse_kansuji(str.chars());
+    assert_eq!(kansuji, Err(KansujiError::UnexpectedChar('5')));
+    let str = "三0";
+    let kansuji = parse_kansuji(str.chars());
+    assert_eq!(kansuji, Err(KansujiError::UnexpectedChar('0')));
+    let str = "1万0";
+    let kansuji = parse_kansuji(str.chars());
+    assert_eq!(kansuji, Err(KansujiError::UnexpectedChar('0')));
+    let str = "1万0001";
+    let kansuji = parse_kansuji(str.chars());
+    assert_eq!(kansuji, Ok(Kansuji::from(10001_u128)));
+}
+
+#[test]
+fn check_parse_kansuji_10() {
+    assert_eq!(Kansuji::try_from("二〇二四"), Ok(Kansuji::from(2024_u128)));
+    assert_eq!(
+        parse_kansuji("一〇万二〇〇".chars()),
+        Ok(Kansuji::from(100200_u128))
+    );
+    assert_eq!(
+        Kansuji::parse_prefix("二〇二四年"),
+        Ok((Kansuji::from(2024_u128), 12))
+    );
+    assert_eq!(parse_kansuji("〇".chars()), Ok(Kansuji::default()));
+    assert_eq!(
+        parse_kansuji("一〇〇〇〇".chars()),
+        Err(KansujiError::UnexpectedChar('〇'))
+    );
+    assert_eq!(
+        parse_kansuji("二〇2".chars()),
+        Err(KansujiError::UnexpectedChar('2'))
+    );
+    assert_eq!(
+        parse_kansuji("十〇".chars()),
+        Err(KansujiError::UnexpectedChar('〇'))
+    );
+}
+
+#[test]
+fn check_parse_kansuji_11() {
+    let v = [
+        ("万", '万'),
+        ("億", '億'),
+        ("分", '分'),
+        ("億万", '億'),
+        ("一億万", '万'),
+        ("億五千万", '億'),
+        ("三万分", '分'),
+    ];
+    for (s, c) in v.iter() {
+        assert_eq!(
+            parse_kansuji(s.chars()),
+            Err(KansujiError::UnexpectedChar(*c))
+        );
+    }
+}
+
+#[test]
+fn check_parse_kansuji_at_1() {
+    let v = [
+        (
+            "二万三万",
+            KansujiError::UnexpectedChar('万'),
+            "二万三".len(),
+        ),
+        ("一二", KansujiError::UnexpectedChar('二'), "一".len()),
+        ("三零", KansujiError::UnexpectedChar('零'), "三".len()),
+        ("三万零", KansujiError::UnexpectedChar('零'), "三万".len()),
+        (
+            "二〇二四五",
+            KansujiError::UnexpectedChar('五'),
+            "二〇二四".len(),
+        ),
+        ("一分二", KansujiError::UnexpectedEnd, "一分二".len()),
+    ];
+    for (s, e, position) in v.iter() {
+        assert_eq!(parse_kansuji_at(s), Err((e.clone(), *position)));
+    }
+    assert_eq!(
+        parse_decimal_at("1.2.3"),
+        Err((KansujiError::UnexpectedChar('.'), 3))
+    );
+}
+
+/// 万・億などや分・厘・毛で区切られた4桁の塊を読む
+///
+/// 数字も位もない空の塊は`None`を返す。
 fn parse_keta(
-    chars: &mut std::iter::Peekable<std::str::Chars>,
-) -> Result<KansujiKeta, KansujiError> {
-    let mut sen = None;
-    let mut hyaku = None;
-    let mut juu = None;
-    let mut iti = None;
+    chars: &mut core::iter::Peekable<core::str::Chars>,
+) -> Result<Option<u16>, KansujiError> {
+    let mut n = 0;
     let mut keta = 4_u8;
-    let mut field = None;
+    let mut digit = None;
+    let mut run_start: Option<core::iter::Peekable<core::str::Chars>> = None;
     while keta > 0 {
-        if let Some(c) = chars.peek() {
-            match c {
-                '一' => {
-                    field = Some(KansujiField::一);
-                    chars.next();
-                }
-                '二' => {
-                    field = Some(KansujiField::二);
-                    chars.next();
-                }
-                '三' => {
-                    field = Some(KansujiField::三);
-                    chars.next();
-                }
-                '四' => {
-                    field = Some(KansujiField::四);
-                    chars.next();
-                }
-                '五' => {
-                    field = Some(KansujiField::五);
-                    chars.next();
-                }
-                '六' => {
-                    field = Some(KansujiField::六);
-                    chars.next();
-                }
-                '七' => {
-                    field = Some(KansujiField::七);
-                    chars.next();
-                }
-                '八' => {
-                    field = Some(KansujiField::八);
-                    chars.next();
-                }
-                '九' => {
-                    field = Some(KansujiField::九);
-                    chars.next();
-                }
-                '千' => {
-                    if keta > 3 {
-                        if let Some(f) = field {
-                            sen = Some(f)
-                        } else {
-                            sen = Some(KansujiField::一)
-                        }
-                        chars.next();
-                        keta = 3;
-                        field = None;
-                    } else {
-                        return Err(KansujiError::UnexpectedChar(*c));
+        let c = match chars.peek() {
+            Some(&c) => c,
+            None => break,
+        };
+        let kind = TokenKind::classify(c);
+        match kind {
+            TokenKind::Digit(d) => {
+                let kanji = arabic_digit(c).is_none();
+                if digit.is_some() {
+                    // 〇を含む漢数字の列（二〇二四など）は算用数字と同じく位取り記数法で読む
+                    match run_start.take() {
+                        Some(start) if kanji && is_positional_run(start.clone()) => *chars = start,
+                        _ => break,
                     }
-                }
-                '百' => {
-                    if keta > 2 {
-                        if let Some(f) = field {
-                            hyaku = Some(f)
-                        } else {
-                            hyaku = Some(KansujiField::一)
-                        }
-                        chars.next();
-                        keta = 2;
-                        field = None;
-                    } else {
-                        return Err(KansujiError::UnexpectedChar(*c));
+                } else if kanji {
+                    // 零は単独でしか使えない
+                    if d == 0 && keta < 4 {
+                        return Err(KansujiError::UnexpectedChar(c));
                     }
-                }
-                '十' => {
-                    if keta > 1 {
-                        if let Some(f) = field {
-                            juu = Some(f)
-                        } else {
-                            juu = Some(KansujiField::一)
-                        }
-                        chars.next();
-                        keta = 1;
-                        field = None;
-                    } else {
-                        return Err(KansujiError::UnexpectedChar(*c));
+                    if keta == 4 {
+                        run_start = Some(chars.clone());
                     }
+                    digit = Some(d as u16);
+                    chars.next();
+                    continue;
                 }
-                '万' | '兆' | '京' | '垓' => {
-                    if let Some(f) = field {
-                        iti = Some(f);
-                    } else {
-                        iti = Some(KansujiField::零);
+                let (m, len) = parse_positional(chars, kanji)?;
+                if len == 1 && m == 0 && keta < 4 {
+                    return Err(KansujiError::UnexpectedChar(c));
+                } else if len == 1 {
+                    digit = Some(m);
+                } else if keta == 4 {
+                    match chars.peek() {
+                        Some(c) if !is_keta_end(TokenKind::classify(*c)) => {
+                            return Err(KansujiError::UnexpectedChar(*c))
+                        }
+                        _ => return Ok(Some(m)),
                     }
-                    break;
+                } else {
+                    return Err(KansujiError::ParseError);
                 }
-                c => return Err(KansujiError::UnexpectedChar(*c)),
             }
-        } else {
-            if let Some(f) = field {
-                iti = Some(f);
-            } else {
-                iti = Some(KansujiField::零);
+            TokenKind::SmallUnit(unit) => {
+                let next = match unit {
+                    1000 => 3,
+                    100 => 2,
+                    _ => 1,
+                };
+                if keta <= next {
+                    return Err(KansujiError::UnexpectedChar(c));
+                }
+                n += digit.take().unwrap_or(1) * unit;
+                run_start = None;
+                chars.next();
+                keta = next;
             }
+            _ if is_keta_end(kind) => break,
+            _ => return Err(KansujiError::UnexpectedChar(c)),
+        }
+    }
+    if keta == 4 && digit.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(n + digit.unwrap_or(0)))
+}
+
+/// 4桁の塊の終わりを表すトークンかどうかを判定する
+fn is_keta_end(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::MyriadUnit(_) | TokenKind::FractionUnit(_) | TokenKind::Other('割')
+    )
+}
+
+/// 〇を含む2文字以上の漢数字の列かどうかを判定する
+fn is_positional_run(chars: core::iter::Peekable<core::str::Chars>) -> bool {
+    let mut len = 0;
+    let mut has_zero = false;
+    for c in chars {
+        if arabic_digit(c).is_some() || !matches!(TokenKind::classify(c), TokenKind::Digit(_)) {
             break;
         }
+        has_zero |= c == '〇';
+        len += 1;
+    }
+    len > 1 && has_zero
+}
+
+/// 位取り記数法で書かれた数字の列を読み、その値と桁数を返す
+///
+/// `kanji`が真なら漢数字の列を、偽なら算用数字（全角を含む）の列を読む。
+/// 4桁の塊の中で使うため、5桁目の数字は`UnexpectedChar`とする。
+fn parse_positional(
+    chars: &mut core::iter::Peekable<core::str::Chars>,
+    kanji: bool,
+) -> Result<(u16, usize), KansujiError> {
+    let mut n = 0;
+    let mut len = 0;
+    while let Some(&c) = chars.peek() {
+        let d = match TokenKind::classify(c) {
+            TokenKind::Digit(d) if arabic_digit(c).is_none() == kanji => d,
+            _ => break,
+        };
+        if len == 4 {
+            return Err(KansujiError::UnexpectedChar(c));
+        }
+        n = n * 10 + d as u16;
+        len += 1;
+        chars.next();
+    }
+    Ok((n, len))
+}
+
+fn arabic_digit(c: char) -> Option<u8> {
+    match c {
+        '0'..='9' => Some(c as u8 - b'0'),
+        '０'..='９' => Some((c as u32 - '０' as u32) as u8),
+        _ => None,
+    }
+}
+
+/// 位取り記数法で使われる漢数字（〇・大字を含む）と算用数字の値を返す
+#[cfg(feature = "alloc")]
+fn positional_digit(c: char) -> Option<u8> {
+    match TokenKind::classify(c) {
+        TokenKind::Digit(d) => Some(d),
+        _ => None,
+    }
+}
+
+/// 整数を読む
+///
+/// `二〇二四`や`2024`のような位取り記数法で書かれたものは桁数に制限なく読み、
+/// それ以外は[`Kansuji`]として読む。小数部がある場合はエラーとする。
+#[cfg(feature = "alloc")]
+fn parse_integer(s: &str) -> Result<u128, KansujiError> {
+    if s.is_empty() {
+        return Err(KansujiError::UnexpectedEnd);
+    }
+    if s.chars().all(|c| positional_digit(c).is_some()) {
+        let mut n: u128 = 0;
+        for d in s.chars().filter_map(positional_digit) {
+            n = n
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(d as u128))
+                .ok_or(KansujiError::TooLarge)?;
+        }
+        return Ok(n);
     }
-    Ok(KansujiKeta {
-        千: sen.unwrap_or(KansujiField::零),
-        百: hyaku.unwrap_or(KansujiField::零),
-        十: juu.unwrap_or(KansujiField::零),
-        一: iti.unwrap_or(KansujiField::零),
-    })
+    let kansuji = Kansuji::try_from(s)?;
+    if kansuji.has_fraction() {
+        return Err(KansujiError::ParseError);
+    }
+    Ok(kansuji.into())
+}
+
+/// 漢数字の整数部に使われる文字かどうかを判定する
+#[cfg(feature = "alloc")]
+fn is_numeral_char(c: char) -> bool {
+    matches!(
+        TokenKind::classify(c),
+        TokenKind::Digit(_) | TokenKind::SmallUnit(_) | TokenKind::MyriadUnit(_)
+    )
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn check_parse_integer_1() {
+    assert_eq!(parse_integer("二〇二四"), Ok(2024));
+    assert_eq!(parse_integer("2024"), Ok(2024));
+    assert_eq!(parse_integer("三"), Ok(3));
+    assert_eq!(parse_integer("二千二十四"), Ok(2024));
+    assert_eq!(parse_integer("一億"), Ok(100000000));
+    assert_eq!(parse_integer(""), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_integer("三分"), Err(KansujiError::ParseError));
 }
 
 #[test]
 fn check_parse_keta_1() {
     let mut chars = "百三十一".chars().peekable();
     let keta = parse_keta(&mut chars);
-    assert_eq!(
-        keta,
-        Ok(KansujiKeta {
-            千: KansujiField::零,
-            百: KansujiField::一,
-            十: KansujiField::三,
-            一: KansujiField::一
-        })
-    )
+    assert_eq!(keta, Ok(Some(131)))
 }
 
 #[test]
 fn check_parse_keta_2() {
     let mut chars = "".chars().peekable();
     let keta = parse_keta(&mut chars);
-    assert_eq!(
-        keta,
-        Ok(KansujiKeta {
-            千: KansujiField::零,
-            百: KansujiField::零,
-            十: KansujiField::零,
-            一: KansujiField::零
-        })
-    )
+    assert_eq!(keta, Ok(None))
 }
 
 #[test]
 fn check_parse_keta_3() {
     let mut chars = "百万一".chars().peekable();
     let keta = parse_keta(&mut chars);
-    assert_eq!(
-        keta,
-        Ok(KansujiKeta {
-            千: KansujiField::零,
-            百: KansujiField::一,
-            十: KansujiField::零,
-            一: KansujiField::零
-        })
-    );
+    assert_eq!(keta, Ok(Some(100)));
     chars.next();
     let keta = parse_keta(&mut chars);
-    assert_eq!(
-        keta,
-        Ok(KansujiKeta {
-            千: KansujiField::零,
-            百: KansujiField::零,
-            十: KansujiField::零,
-            一: KansujiField::一
-        })
-    )
+    assert_eq!(keta, Ok(Some(1)))
 }
 
 #[test]
@@ -639,124 +1113,52 @@ fn check_parse_keta_4() {
 fn check_parse_keta_5() {
     let mut chars = "百二万一".chars().peekable();
     let keta = parse_keta(&mut chars);
-    assert_eq!(
-        keta,
-        Ok(KansujiKeta {
-            千: KansujiField::零,
-            百: KansujiField::一,
-            十: KansujiField::零,
-            一: KansujiField::二
-        })
-    );
+    assert_eq!(keta, Ok(Some(102)));
     chars.next();
     let keta = parse_keta(&mut chars);
-    assert_eq!(
-        keta,
-        Ok(KansujiKeta {
-            千: KansujiField::零,
-            百: KansujiField::零,
-            十: KansujiField::零,
-            一: KansujiField::一
-        })
-    )
+    assert_eq!(keta, Ok(Some(1)))
 }
 
 #[test]
 fn check_parse_keta_6() {
     let mut chars = "五百".chars().peekable();
     let keta = parse_keta(&mut chars);
-    assert_eq!(
-        keta,
-        Ok(KansujiKeta {
-            千: KansujiField::零,
-            百: KansujiField::五,
-            十: KansujiField::零,
-            一: KansujiField::零
-        })
-    );
+    assert_eq!(keta, Ok(Some(500)));
 }
 
 #[test]
 fn check_parse_keta_7() {
     let mut chars = "五百一".chars().peekable();
     let keta = parse_keta(&mut chars);
-    assert_eq!(
-        keta,
-        Ok(KansujiKeta {
-            千: KansujiField::零,
-            百: KansujiField::五,
-            十: KansujiField::零,
-            一: KansujiField::一
-        })
-    )
+    assert_eq!(keta, Ok(Some(501)))
 }
 
 impl From<Kansuji> for f64 {
     fn from(value: Kansuji) -> Self {
-        let mut n = 0;
-        n += Into::<usize>::into(value.一) as u128;
-        n += (Into::<usize>::into(value.万) as u128) * 10000;
-        n += (Into::<usize>::into(value.億) as u128) * 100000000;
-        n += (Into::<usize>::into(value.兆) as u128) * 1000000000000;
-        n += (Into::<usize>::into(value.京) as u128) * 10000000000000000;
-        n += (Into::<usize>::into(value.垓) as u128) * 100000000000000000000;
-        let mut n2 = 0;
-        n2 += value.分.to_int() as usize * 100;
-        n2 += value.厘.to_int() as usize * 10;
-        n2 += value.毛.to_int() as usize;
-        n as f64 + (n2 as f64 * 0.001)
+        (value.値 / 1000) as f64 + ((value.値 % 1000) as f64 * 0.001)
     }
 }
 
 impl From<Kansuji> for f32 {
     fn from(value: Kansuji) -> Self {
-        let mut n = 0;
-        n += Into::<usize>::into(value.一) as u128;
-        n += (Into::<usize>::into(value.万) as u128) * 10000;
-        n += (Into::<usize>::into(value.億) as u128) * 100000000;
-        n += (Into::<usize>::into(value.兆) as u128) * 1000000000000;
-        n += (Into::<usize>::into(value.京) as u128) * 10000000000000000;
-        n += (Into::<usize>::into(value.垓) as u128) * 100000000000000000000;
-        let mut n2 = 0;
-        n2 += value.分.to_int() as usize * 100;
-        n2 += value.厘.to_int() as usize * 10;
-        n2 += value.毛.to_int() as usize;
-        n as f32 + (n2 as f32 * 0.001)
+        (value.値 / 1000) as f32 + ((value.値 % 1000) as f32 * 0.001)
     }
 }
 
 impl From<Kansuji> for u128 {
     fn from(value: Kansuji) -> Self {
-        let mut n = 0;
-        n += Into::<usize>::into(value.一) as u128;
-        n += (Into::<usize>::into(value.万) as u128) * 10000;
-        n += (Into::<usize>::into(value.億) as u128) * 100000000;
-        n += (Into::<usize>::into(value.兆) as u128) * 1000000000000;
-        n += (Into::<usize>::into(value.京) as u128) * 10000000000000000;
-        n += (Into::<usize>::into(value.垓) as u128) * 100000000000000000000;
-        n
+        value.to_u128()
     }
 }
 
+/// 漢数字で表せる整数部の上限（含まない、10の24乗）
+///
+/// [`Kansuji::from`]などで整数から作る場合、これ以上の値は上限で割った余りになる。
+pub const KANSUJI_LIMIT: u128 = 1_0000_0000_0000_0000_0000_0000;
+
 impl From<u128> for Kansuji {
     fn from(value: u128) -> Self {
-        let gai = value / 100000000000000000000;
-        let kei = (value % 100000000000000000000) / 10000000000000000;
-        let tyou = (value % 10000000000000000) / 1000000000000;
-        let oku = (value % 1000000000000) / 100000000;
-        let man = (value % 100000000) / 10000;
-        let iti = value % 10000;
-        Kansuji {
-            垓: KansujiKeta::from(gai as usize),
-            京: KansujiKeta::from(kei as usize),
-            兆: KansujiKeta::from(tyou as usize),
-            億: KansujiKeta::from(oku as usize),
-            万: KansujiKeta::from(man as usize),
-            一: KansujiKeta::from(iti as usize),
-            分: KansujiField::零,
-            厘: KansujiField::零,
-            毛: KansujiField::零,
-        }
+        Kansuji::from_u128(value)
     }
 }
 
@@ -769,111 +1171,60 @@ impl From<usize> for Kansuji {
 
 impl From<u64> for Kansuji {
     fn from(value: u64) -> Self {
-        let kei = value / 10000000000000000;
-        let tyou = (value % 10000000000000000) / 1000000000000;
-        let oku = (value % 1000000000000) / 100000000;
-        let man = (value % 100000000) / 10000;
-        let iti = value % 10000;
-        Kansuji {
-            垓: KansujiKeta::default(),
-            京: KansujiKeta::from(kei as usize),
-            兆: KansujiKeta::from(tyou as usize),
-            億: KansujiKeta::from(oku as usize),
-            万: KansujiKeta::from(man as usize),
-            一: KansujiKeta::from(iti as usize),
-            分: KansujiField::零,
-            厘: KansujiField::零,
-            毛: KansujiField::零,
-        }
+        Kansuji::from(value as u128)
     }
 }
 
 impl From<u32> for Kansuji {
     fn from(value: u32) -> Self {
-        let oku = value / 100000000;
-        let man = (value % 100000000) / 10000;
-        let iti = value % 10000;
-        Kansuji {
-            垓: KansujiKeta::default(),
-            京: KansujiKeta::default(),
-            兆: KansujiKeta::default(),
-            億: KansujiKeta::from(oku as usize),
-            万: KansujiKeta::from(man as usize),
-            一: KansujiKeta::from(iti as usize),
-            分: KansujiField::零,
-            厘: KansujiField::零,
-            毛: KansujiField::零,
-        }
+        Kansuji::from(value as u128)
     }
 }
 
 impl From<u16> for Kansuji {
     fn from(value: u16) -> Self {
-        let man = value / 10000;
-        let iti = value % 10000;
-        Kansuji {
-            垓: KansujiKeta::default(),
-            京: KansujiKeta::default(),
-            兆: KansujiKeta::default(),
-            億: KansujiKeta::default(),
-            万: KansujiKeta::from(man as usize),
-            一: KansujiKeta::from(iti as usize),
-            分: KansujiField::零,
-            厘: KansujiField::零,
-            毛: KansujiField::零,
-        }
+        Kansuji::from(value as u128)
     }
 }
 
 impl From<u8> for Kansuji {
     fn from(value: u8) -> Self {
-        Kansuji {
-            垓: KansujiKeta::default(),
-            京: KansujiKeta::default(),
-            兆: KansujiKeta::default(),
-            億: KansujiKeta::default(),
-            万: KansujiKeta::default(),
-            一: KansujiKeta::from(value as usize),
-            分: KansujiField::零,
-            厘: KansujiField::零,
-            毛: KansujiField::零,
+        Kansuji::from(value as u128)
+    }
+}
+
+/// 小数を十進の文字列にしたときの小数点以下の先頭三桁を、毛を単位とした整数として読む
+#[derive(Default)]
+struct FractionDigits {
+    小数部: bool,
+    桁数: u32,
+    毛: u128,
+}
+
+impl fmt::Write for FractionDigits {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if c == '.' {
+                self.小数部 = true;
+            } else if self.小数部 && self.桁数 < 3 {
+                if let Some(d) = c.to_digit(10) {
+                    self.毛 += d as u128 * 10_u128.pow(2 - self.桁数);
+                    self.桁数 += 1;
+                }
+            }
         }
+        Ok(())
     }
 }
 
 impl From<f64> for Kansuji {
     fn from(value: f64) -> Self {
         let n = value as u128;
-        let gai = n / 100000000000000000000;
-        let kei = (n % 100000000000000000000) / 10000000000000000;
-        let tyou = (n % 10000000000000000) / 1000000000000;
-        let oku = (n % 1000000000000) / 100000000;
-        let man = (n % 100000000) / 10000;
-        let iti = n % 10000;
-        let f_str = value.to_string();
-        let mut f_chars = f_str.split('.').nth(1).unwrap_or_default().chars();
-        let bu = f_chars
-            .next()
-            .and_then(|c| c.to_string().parse::<u8>().ok())
-            .unwrap_or_default();
-        let rin = f_chars
-            .next()
-            .and_then(|c| c.to_string().parse::<u8>().ok())
-            .unwrap_or_default();
-        let mou = f_chars
-            .next()
-            .and_then(|c| c.to_string().parse::<u8>().ok())
-            .unwrap_or_default();
+        let mut fraction = FractionDigits::default();
+        // FractionDigitsへの書き込みは失敗しない
+        let _ = fmt::write(&mut fraction, format_args!("{}", value));
         Kansuji {
-            垓: KansujiKeta::from(gai as usize),
-            京: KansujiKeta::from(kei as usize),
-            兆: KansujiKeta::from(tyou as usize),
-            億: KansujiKeta::from(oku as usize),
-            万: KansujiKeta::from(man as usize),
-            一: KansujiKeta::from(iti as usize),
-            分: KansujiField::from_int(bu),
-            厘: KansujiField::from_int(rin),
-            毛: KansujiField::from_int(mou),
+            値: n % KANSUJI_LIMIT * 1000 + fraction.毛,
         }
     }
 }
@@ -881,27 +1232,10 @@ impl From<f64> for Kansuji {
 impl From<f32> for Kansuji {
     fn from(value: f32) -> Self {
         let n = value as u128;
-        let gai = n / 100000000000000000000;
-        let kei = (n % 100000000000000000000) / 10000000000000000;
-        let tyou = (n % 10000000000000000) / 1000000000000;
-        let oku = (n % 1000000000000) / 100000000;
-        let man = (n % 100000000) / 10000;
-        let iti = n % 10000;
         let f = value - (n as f32);
-        let f = (f * 1000.0) as usize;
-        let bu = f / 100;
-        let rin = (f % 100) / 10;
-        let mou = f % 10;
+        let f = (f * 1000.0) as u128;
         Kansuji {
-            垓: KansujiKeta::from(gai as usize),
-            京: KansujiKeta::from(kei as usize),
-            兆: KansujiKeta::from(tyou as usize),
-            億: KansujiKeta::from(oku as usize),
-            万: KansujiKeta::from(man as usize),
-            一: KansujiKeta::from(iti as usize),
-            分: KansujiField::from_int(bu as u8),
-            厘: KansujiField::from_int(rin as u8),
-            毛: KansujiField::from_int(mou as u8),
+            値: n % KANSUJI_LIMIT * 1000 + f % 1000,
         }
     }
 }
@@ -954,54 +1288,202 @@ impl From<&f32> for Kansuji {
     }
 }
 
-impl ToString for Kansuji {
-    fn to_string(&self) -> String {
-        let mut s = String::new();
-        if self.垓.is_zero()
-            && self.京.is_zero()
-            && self.兆.is_zero()
-            && self.億.is_zero()
-            && self.万.is_zero()
-            && self.一.is_zero()
-            && self.分 == KansujiField::零
-            && self.厘 == KansujiField::零
-            && self.毛 == KansujiField::零
-        {
-            return "零".to_string();
-        }
-        if !self.垓.is_zero() {
-            s.push_str(&format!("{}垓", self.垓.to_string()))
-        }
-        if !self.京.is_zero() {
-            s.push_str(&format!("{}京", self.京.to_string()))
+/// 漢数字への変換時の書式
+///
+/// ```
+/// use kansuji::{Kansuji, KansujiBuffer, KansujiFormat};
+///
+/// let kansuji = Kansuji::from(35000_u32);
+/// let format = KansujiFormat {
+///     daiji: true,
+///     ..KansujiFormat::default()
+/// };
+/// let mut buffer = KansujiBuffer::new();
+/// assert_eq!(buffer.format(kansuji, &format), "参萬伍阡");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct KansujiFormat {
+    /// 大字（壱・弐・参・拾・萬など）を使う
+    ///
+    /// 大字では改竄を防ぐため、拾・佰・阡の前の壱も省略しない。
+    pub daiji: bool,
+    /// 千の前の一を省略しない（一千）
+    pub explicit_one: bool,
+}
+
+impl Kansuji {
+    fn has_fraction(&self) -> bool {
+        self.値 % 1000 != 0
+    }
+
+    /// 毛（千分の一）を単位とした整数に変換する
+    pub const fn to_mou(self) -> u128 {
+        self.値
+    }
+
+    /// 毛（千分の一）を単位とした整数から作る
+    ///
+    /// 垓の位を超える部分は切り捨てる。
+    pub const fn from_mou(n: u128) -> Self {
+        Kansuji {
+            値: n % (KANSUJI_LIMIT * 1000),
         }
-        if !self.兆.is_zero() {
-            s.push_str(&format!("{}兆", self.兆.to_string()))
+    }
+
+    /// 整数から作る
+    ///
+    /// `Kansuji::from`と同じく、垓の位を超える部分は切り捨てる。
+    ///
+    /// ```
+    /// use kansuji::Kansuji;
+    ///
+    /// const SANBYAKU: Kansuji = Kansuji::from_u128(300);
+    /// assert_eq!(SANBYAKU.to_string(), "三百");
+    /// ```
+    pub const fn from_u128(n: u128) -> Self {
+        Kansuji {
+            値: n % KANSUJI_LIMIT * 1000,
         }
-        if !self.億.is_zero() {
-            s.push_str(&format!("{}億", self.億.to_string()))
+    }
+
+    /// 整数から作る
+    pub const fn from_u64(n: u64) -> Self {
+        Kansuji::from_u128(n as u128)
+    }
+
+    /// 整数から作る
+    pub const fn from_u32(n: u32) -> Self {
+        Kansuji::from_u128(n as u128)
+    }
+
+    /// 整数部を返す
+    pub const fn to_u128(self) -> u128 {
+        self.値 / 1000
+    }
+
+    /// 万以上の位と一の位の四桁のまとまりを返す
+    fn keta(self, kurai: Kurai) -> KansujiKeta {
+        KansujiKeta::from(self.get(kurai) as usize)
+    }
+
+    /// 分・厘・毛の位の数字を返す
+    fn field(self, kurai: Kurai) -> KansujiField {
+        KansujiField::from_int(self.get(kurai) as u8)
+    }
+
+    /// 書式を指定して漢数字に変換する
+    #[cfg(feature = "alloc")]
+    pub fn to_string_with(&self, format: &KansujiFormat) -> String {
+        let mut s = String::new();
+        // Stringへの書き込みは失敗しない
+        let _ = self.write_to(&mut s, format);
+        s
+    }
+
+    /// 書式を指定して漢数字を書き込む
+    ///
+    /// 途中で文字列を確保しないため、[`KansujiBuffer`]のような固定長の書き込み先にも使える。
+    pub fn write_to<W: fmt::Write>(&self, f: &mut W, format: &KansujiFormat) -> fmt::Result {
+        if self.値 == 0 {
+            return f.write_str("零");
         }
-        if !self.万.is_zero() {
-            s.push_str(&format!("{}万", self.万.to_string()))
+        let man = if format.daiji { "萬" } else { "万" };
+        for (keta, unit) in [
+            (self.keta(Kurai::垓), "垓"),
+            (self.keta(Kurai::京), "京"),
+            (self.keta(Kurai::兆), "兆"),
+            (self.keta(Kurai::億), "億"),
+            (self.keta(Kurai::万), man),
+        ] {
+            if !keta.is_zero() {
+                keta.write_with(f, format)?;
+                f.write_str(unit)?;
+            }
         }
-        if self.一.is_one() {
-            s.push('一')
+        let ichi = self.keta(Kurai::一);
+        if ichi.is_one() && !format.daiji {
+            f.write_str("一")?;
         } else {
-            s.push_str(&self.一.to_string())
+            ichi.write_with(f, format)?;
         }
-        if self.分 != KansujiField::零 {
-            s.push_str(&format!("{}分", self.分.to_str2()))
+        for (kurai, unit) in [(Kurai::分, "分"), (Kurai::厘, "厘"), (Kurai::毛, "毛")] {
+            let field = self.field(kurai);
+            if field != KansujiField::零 {
+                if format.daiji {
+                    f.write_str(field.to_daiji())?;
+                } else {
+                    f.write_str(field.to_str2())?;
+                }
+                f.write_str(unit)?;
+            }
         }
-        if self.厘 != KansujiField::零 {
-            s.push_str(&format!("{}厘", self.厘.to_str2()))
+        Ok(())
+    }
+
+    /// `320`や`1.5`のような十進の表記で書き込む
+    ///
+    /// 小数部の末尾の0は書かない。
+    pub fn write_decimal<W: fmt::Write>(&self, f: &mut W) -> fmt::Result {
+        write!(f, "{}", self.値 / 1000)?;
+        let mut fraction = self.値 % 1000;
+        if fraction == 0 {
+            return Ok(());
         }
-        if self.毛 != KansujiField::零 {
-            s.push_str(&format!("{}毛", self.毛.to_str2()))
+        let mut digits = 3;
+        while fraction % 10 == 0 {
+            fraction /= 10;
+            digits -= 1;
         }
+        write!(f, ".{:01$}", fraction, digits)
+    }
+
+    /// `320`や`1.5`のような十進の表記に変換する
+    ///
+    /// 小数部の末尾の0は書かない。
+    #[cfg(feature = "alloc")]
+    pub fn to_decimal_string(&self) -> String {
+        let mut s = String::new();
+        // Stringへの書き込みは失敗しない
+        let _ = self.write_decimal(&mut s);
         s
     }
+
+    /// 書式を指定して漢数字をバイト列として書き込む
+    #[cfg(feature = "std")]
+    pub fn write_io<W: std::io::Write>(
+        &self,
+        w: &mut W,
+        format: &KansujiFormat,
+    ) -> std::io::Result<()> {
+        let mut buffer = KansujiBuffer::new();
+        w.write_all(buffer.format(*self, format).as_bytes())
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn check_kansuji_write_1() {
+    let format = KansujiFormat::default();
+    let mut s = String::from("金");
+    Kansuji::from(35000_u32).write_to(&mut s, &format).unwrap();
+    assert_eq!(s, "金三万五千");
+    let mut v: Vec<u8> = Vec::new();
+    Kansuji::from(1.5).write_io(&mut v, &format).unwrap();
+    assert_eq!(v, "一五分".as_bytes());
+    let mut s = String::new();
+    Kansuji::from_mou(320_050).write_decimal(&mut s).unwrap();
+    assert_eq!(s, "320.05");
+    assert_eq!(Kansuji::from_mou(320_050).to_decimal_string(), "320.05");
+    assert_eq!(Kansuji::from(12_u8).to_decimal_string(), "12");
+}
+
+impl fmt::Display for Kansuji {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f, &KansujiFormat::default())
+    }
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn check_kansuji_1() {
     fn kansuji_test_function(n: &u128) {
@@ -1016,9 +1498,10 @@ fn check_kansuji_1() {
     let v = vec![
         0, 1, 2, 3, 10, 11, 15, 200, 210501, 76492334, 764923341, 1999999,
     ];
-    let _ = v.iter().map(kansuji_test_function);
+    v.iter().for_each(kansuji_test_function);
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn check_kansuji_2() {
     let f = 1.234;
@@ -1027,6 +1510,7 @@ fn check_kansuji_2() {
     assert_eq!(s, "一二分三厘四毛".to_string());
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn check_kansuji_3() {
     let f = 1.203;
@@ -1035,6 +1519,7 @@ fn check_kansuji_3() {
     assert_eq!(s, "一二分三毛".to_string());
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn check_kansuji_4() {
     let f = 1.204;
@@ -1043,6 +1528,7 @@ fn check_kansuji_4() {
     assert_eq!(s, "一二分四毛".to_string());
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn check_kansuji_4_2() {
     let f = 3.456;
@@ -1051,6 +1537,46 @@ fn check_kansuji_4_2() {
     assert_eq!(s, "三四分五厘六毛".to_string());
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn check_kansuji_4_3() {
+    let format = KansujiFormat {
+        daiji: true,
+        ..KansujiFormat::default()
+    };
+    let kansuji = Kansuji::from(10011_u128);
+    assert_eq!(kansuji.to_string_with(&format), "壱萬壱拾壱");
+    let kansuji = Kansuji::from(1.5);
+    assert_eq!(kansuji.to_string_with(&format), "壱伍分");
+    let s = Kansuji::from(123456789_u128).to_string_with(&format);
+    assert_eq!(s, "壱億弐阡参佰肆拾伍萬陸阡漆佰捌拾玖");
+    assert_eq!(Kansuji::try_from(&s), Ok(Kansuji::from(123456789_u128)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn check_kansuji_4_4() {
+    let format = KansujiFormat {
+        explicit_one: true,
+        ..KansujiFormat::default()
+    };
+    let kansuji = Kansuji::from(11100_u128);
+    assert_eq!(kansuji.to_string_with(&format), "一万一千百");
+    let kansuji = Kansuji::from(1100_u128);
+    assert_eq!(kansuji.to_string_with(&format), "一千百");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn check_kansuji_4_5() {
+    assert_eq!(core::mem::size_of::<Kansuji>(), 16);
+    let mut set = std::collections::HashSet::new();
+    set.insert(Kansuji::from(1.5));
+    assert!(set.contains(&Kansuji::try_from("一五分").unwrap()));
+    assert!(!set.contains(&Kansuji::from(1_u8)));
+}
+
+#[cfg(feature = "alloc")]
 #[test]
 fn check_kansuji_5() {
     let n: usize = 210501;
@@ -1058,25 +1584,18 @@ fn check_kansuji_5() {
     let kansuji = Kansuji::try_from(s).unwrap();
     assert_eq!(
         kansuji,
-        Kansuji {
-            万: KansujiKeta {
-                十: KansujiField::二,
-                一: KansujiField::一,
-                ..KansujiKeta::default()
-            },
-            一: KansujiKeta {
-                百: KansujiField::五,
-                一: KansujiField::一,
-                ..KansujiKeta::default()
-            },
-            ..Kansuji::default()
-        }
+        Kansuji::builder()
+            .set(Kurai::万, 21)
+            .set(Kurai::一, 501)
+            .build()
+            .unwrap()
     );
     assert_eq!(Kansuji::from(n), kansuji);
     let s2 = kansuji.to_string();
     assert_eq!(s, s2);
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn check_kansuji_6() {
     let n: usize = 200500;
@@ -1084,17 +1603,11 @@ fn check_kansuji_6() {
     let kansuji = Kansuji::try_from(s).unwrap();
     assert_eq!(
         kansuji,
-        Kansuji {
-            万: KansujiKeta {
-                十: KansujiField::二,
-                ..KansujiKeta::default()
-            },
-            一: KansujiKeta {
-                百: KansujiField::五,
-                ..KansujiKeta::default()
-            },
-            ..Kansuji::default()
-        }
+        Kansuji::builder()
+            .set(Kurai::万, 20)
+            .set(Kurai::一, 500)
+            .build()
+            .unwrap()
     );
     assert_eq!(Kansuji::from(n), kansuji);
     let s2 = kansuji.to_string();
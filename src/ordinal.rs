@@ -0,0 +1,439 @@
+//! 序数（第三・三番目など）と、法令の条項（第三条第二項第一号・第八条の二）の解析と変換を行う
+//!
+//! ```
+//! use kansuji::{Ordinal, OrdinalKind, OrdinalPath};
+//!
+//! let ordinal = Ordinal::try_from("三番目").unwrap();
+//! assert_eq!(ordinal.kind(), OrdinalKind::番目);
+//! assert_eq!(u128::from(ordinal.kansuji()), 3);
+//!
+//! let path = OrdinalPath::try_from("第八条の二第一項").unwrap();
+//! assert_eq!(path.elements()[0].unit(), Some("条"));
+//! assert_eq!(u128::from(path.elements()[0].branches()[0]), 2);
+//! assert_eq!(path.elements()[1].unit(), Some("項"));
+//! assert_eq!(path.to_string(), "第八条の二第一項");
+//! ```
+
+use super::{is_numeral_char, Kansuji, KansujiError};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::{From, TryFrom};
+use core::fmt;
+
+/// 序数の書き方
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum OrdinalKind {
+    /// 第三・第三条
+    第,
+    /// 三番目
+    番目,
+    /// 三番
+    番,
+    /// 三次
+    次,
+}
+
+/// 序数
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Ordinal {
+    数: Kansuji,
+    形式: OrdinalKind,
+    単位: Option<String>,
+    枝番号: Vec<Kansuji>,
+}
+
+impl Ordinal {
+    /// 単位と枝番号のない序数を作る
+    pub fn new(kansuji: Kansuji, kind: OrdinalKind) -> Self {
+        Ordinal {
+            数: kansuji,
+            形式: kind,
+            単位: None,
+            枝番号: Vec::new(),
+        }
+    }
+
+    /// `第…条`のような単位と、`の二`のような枝番号を持つ序数を作る
+    pub fn with_unit(kansuji: Kansuji, unit: &str, branches: Vec<Kansuji>) -> Self {
+        Ordinal {
+            数: kansuji,
+            形式: OrdinalKind::第,
+            単位: Some(unit.to_string()),
+            枝番号: branches,
+        }
+    }
+
+    /// 数を返す
+    pub fn kansuji(&self) -> Kansuji {
+        self.数
+    }
+
+    /// 書き方を返す
+    pub fn kind(&self) -> OrdinalKind {
+        self.形式
+    }
+
+    /// `第三条`の`条`のような単位を返す
+    pub fn unit(&self) -> Option<&str> {
+        self.単位.as_deref()
+    }
+
+    /// `第八条の二`の`二`のような枝番号を返す
+    pub fn branches(&self) -> &[Kansuji] {
+        &self.枝番号
+    }
+}
+
+impl TryFrom<String> for Ordinal {
+    type Error = KansujiError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_ordinal(&value)
+    }
+}
+
+impl TryFrom<&String> for Ordinal {
+    type Error = KansujiError;
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        parse_ordinal(value)
+    }
+}
+
+impl TryFrom<&str> for Ordinal {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_ordinal(value)
+    }
+}
+
+fn parse_ordinal(s: &str) -> Result<Ordinal, KansujiError> {
+    let (ordinal, rest) = parse_ordinal_prefix(s)?;
+    if let Some(c) = rest.chars().next() {
+        return Err(KansujiError::UnexpectedChar(c));
+    }
+    Ok(ordinal)
+}
+
+/// 先頭の漢数字を読み、数と残りの文字列を返す
+fn split_numeral(s: &str) -> Result<(Kansuji, &str), KansujiError> {
+    let i = s
+        .char_indices()
+        .find(|(_, c)| !is_numeral_char(*c))
+        .map_or(s.len(), |(i, _)| i);
+    let (n, rest) = s.split_at(i);
+    if n.is_empty() {
+        return Err(rest
+            .chars()
+            .next()
+            .map_or(KansujiError::UnexpectedEnd, KansujiError::UnexpectedChar));
+    }
+    Ok((Kansuji::try_from(n)?, rest))
+}
+
+/// `の`の後ろに漢数字が続くかどうかを判定する
+fn starts_with_branch(s: &str) -> bool {
+    s.strip_prefix('の')
+        .and_then(|s| s.chars().next())
+        .map_or(false, is_numeral_char)
+}
+
+/// `第三条`の`条`のように`第`の序数に続く単位
+///
+/// `第三番目`の`番目`のように長いものを先に置く。
+const ORDINAL_UNITS: &[&str] = &[
+    "番目", "編", "章", "節", "款", "目", "条", "項", "号", "回", "位", "部", "巻", "話", "期",
+    "代", "版", "弾", "類", "種", "級", "段", "次", "番",
+];
+
+/// 条項として連ねられる単位（上位のものから順に並べる）
+const PATH_UNITS: &[&str] = &["編", "章", "節", "款", "目", "条", "項", "号"];
+
+/// 先頭の序数を一つ読み、序数と残りの文字列を返す
+fn parse_ordinal_prefix(s: &str) -> Result<(Ordinal, &str), KansujiError> {
+    if let Some(s) = s.strip_prefix('第') {
+        let (kansuji, mut rest) = split_numeral(s)?;
+        let unit = ORDINAL_UNITS
+            .iter()
+            .find(|unit| rest.starts_with(*unit))
+            .map_or("", |unit| *unit);
+        rest = &rest[unit.len()..];
+        let mut branches = Vec::new();
+        while starts_with_branch(rest) {
+            let (branch, r) = split_numeral(&rest['の'.len_utf8()..])?;
+            branches.push(branch);
+            rest = r;
+        }
+        let ordinal = Ordinal {
+            数: kansuji,
+            形式: OrdinalKind::第,
+            単位: if unit.is_empty() {
+                None
+            } else {
+                Some(unit.to_string())
+            },
+            枝番号: branches,
+        };
+        return Ok((ordinal, rest));
+    }
+    let (kansuji, rest) = split_numeral(s)?;
+    for (suffix, kind) in [
+        ("番目", OrdinalKind::番目),
+        ("番", OrdinalKind::番),
+        ("次", OrdinalKind::次),
+    ] {
+        if let Some(rest) = rest.strip_prefix(suffix) {
+            return Ok((Ordinal::new(kansuji, kind), rest));
+        }
+    }
+    Err(rest
+        .chars()
+        .next()
+        .map_or(KansujiError::UnexpectedEnd, KansujiError::UnexpectedChar))
+}
+
+#[test]
+fn check_parse_ordinal_1() {
+    assert_eq!(
+        parse_ordinal("第三"),
+        Ok(Ordinal::new(Kansuji::from(3_u8), OrdinalKind::第))
+    );
+    assert_eq!(
+        parse_ordinal("三番目"),
+        Ok(Ordinal::new(Kansuji::from(3_u8), OrdinalKind::番目))
+    );
+    assert_eq!(
+        parse_ordinal("十二番"),
+        Ok(Ordinal::new(Kansuji::from(12_u8), OrdinalKind::番))
+    );
+    assert_eq!(
+        parse_ordinal("二次"),
+        Ok(Ordinal::new(Kansuji::from(2_u8), OrdinalKind::次))
+    );
+    assert_eq!(
+        parse_ordinal("第一章"),
+        Ok(Ordinal::with_unit(Kansuji::from(1_u8), "章", Vec::new()))
+    );
+    assert_eq!(
+        parse_ordinal("第三番目"),
+        Ok(Ordinal::with_unit(Kansuji::from(3_u8), "番目", Vec::new()))
+    );
+    assert_eq!(
+        parse_ordinal("第八条の二の三"),
+        Ok(Ordinal::with_unit(
+            Kansuji::from(8_u8),
+            "条",
+            vec![Kansuji::from(2_u8), Kansuji::from(3_u8)]
+        ))
+    );
+}
+
+#[test]
+fn check_parse_ordinal_2() {
+    assert_eq!(parse_ordinal("第"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(
+        parse_ordinal("第条"),
+        Err(KansujiError::UnexpectedChar('条'))
+    );
+    assert_eq!(parse_ordinal("三"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(
+        parse_ordinal("三個"),
+        Err(KansujiError::UnexpectedChar('個'))
+    );
+    assert_eq!(
+        parse_ordinal("第一条第二項"),
+        Err(KansujiError::UnexpectedChar('第'))
+    );
+    assert_eq!(
+        parse_ordinal("第三者"),
+        Err(KansujiError::UnexpectedChar('者'))
+    );
+    assert_eq!(
+        parse_ordinal("第三条に規定する"),
+        Err(KansujiError::UnexpectedChar('に'))
+    );
+}
+
+impl fmt::Display for Ordinal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.形式 {
+            OrdinalKind::第 => write!(f, "第{}", self.数)?,
+            OrdinalKind::番目 => write!(f, "{}番目", self.数)?,
+            OrdinalKind::番 => write!(f, "{}番", self.数)?,
+            OrdinalKind::次 => write!(f, "{}次", self.数)?,
+        }
+        if let Some(unit) = &self.単位 {
+            write!(f, "{}", unit)?;
+        }
+        for branch in self.枝番号.iter() {
+            write!(f, "の{}", branch)?;
+        }
+        Ok(())
+    }
+}
+
+/// 法令の条項のように連なった序数
+///
+/// 文字列から読む場合、序数を連ねられるのは編・章・節・款・目・条・項・号の単位を持つ`第…`で、
+/// 上位の単位から順に並んでいなければならない。単位のない序数（`第二`・`三番目`など）は最後にだけ置ける。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct OrdinalPath {
+    要素: Vec<Ordinal>,
+}
+
+impl OrdinalPath {
+    /// 序数を並べたものを返す
+    pub fn elements(&self) -> &[Ordinal] {
+        &self.要素
+    }
+}
+
+impl From<Vec<Ordinal>> for OrdinalPath {
+    fn from(value: Vec<Ordinal>) -> Self {
+        OrdinalPath { 要素: value }
+    }
+}
+
+impl TryFrom<String> for OrdinalPath {
+    type Error = KansujiError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_ordinal_path(&value)
+    }
+}
+
+impl TryFrom<&String> for OrdinalPath {
+    type Error = KansujiError;
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        parse_ordinal_path(value)
+    }
+}
+
+impl TryFrom<&str> for OrdinalPath {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_ordinal_path(value)
+    }
+}
+
+fn parse_ordinal_path(s: &str) -> Result<OrdinalPath, KansujiError> {
+    let (ordinal, mut rest) = parse_ordinal_prefix(s)?;
+    let mut path = vec![ordinal];
+    while let Some(c) = rest.chars().next() {
+        let level = match path.last().and_then(path_level) {
+            Some(level) if c == '第' => level,
+            _ => return Err(KansujiError::UnexpectedChar(c)),
+        };
+        let (ordinal, r) = parse_ordinal_prefix(rest)?;
+        if let Some(unit) = ordinal.unit() {
+            if !path_level(&ordinal).map_or(false, |l| l > level) {
+                let c = unit.chars().next().unwrap_or('第');
+                return Err(KansujiError::UnexpectedChar(c));
+            }
+        }
+        path.push(ordinal);
+        rest = r;
+    }
+    Ok(OrdinalPath { 要素: path })
+}
+
+/// 条項の単位の階層（上位ほど小さい）を返す
+fn path_level(ordinal: &Ordinal) -> Option<usize> {
+    let unit = ordinal.unit()?;
+    PATH_UNITS.iter().position(|u| *u == unit)
+}
+
+#[test]
+fn check_parse_ordinal_path_1() {
+    assert_eq!(
+        parse_ordinal_path("第三条第二項第一号"),
+        Ok(OrdinalPath::from(vec![
+            Ordinal::with_unit(Kansuji::from(3_u8), "条", Vec::new()),
+            Ordinal::with_unit(Kansuji::from(2_u8), "項", Vec::new()),
+            Ordinal::with_unit(Kansuji::from(1_u8), "号", Vec::new()),
+        ]))
+    );
+    assert_eq!(
+        parse_ordinal_path("第八条の二"),
+        Ok(OrdinalPath::from(vec![Ordinal::with_unit(
+            Kansuji::from(8_u8),
+            "条",
+            vec![Kansuji::from(2_u8)]
+        )]))
+    );
+    assert_eq!(
+        parse_ordinal_path("三番目"),
+        Ok(OrdinalPath::from(vec![Ordinal::new(
+            Kansuji::from(3_u8),
+            OrdinalKind::番目
+        )]))
+    );
+}
+
+#[test]
+fn check_parse_ordinal_path_2() {
+    assert_eq!(
+        parse_ordinal_path("第三条及び第四条"),
+        Err(KansujiError::UnexpectedChar('及'))
+    );
+    assert_eq!(
+        parse_ordinal_path("第三条に規定する"),
+        Err(KansujiError::UnexpectedChar('に'))
+    );
+    assert_eq!(
+        parse_ordinal_path("三番目第二"),
+        Err(KansujiError::UnexpectedChar('第'))
+    );
+    assert_eq!(
+        parse_ordinal_path("第三第三"),
+        Err(KansujiError::UnexpectedChar('第'))
+    );
+    assert_eq!(
+        parse_ordinal_path("第二項第三条"),
+        Err(KansujiError::UnexpectedChar('条'))
+    );
+    assert_eq!(
+        parse_ordinal_path("第三条第二番目"),
+        Err(KansujiError::UnexpectedChar('番'))
+    );
+    assert_eq!(
+        parse_ordinal_path("第一章第二"),
+        Ok(OrdinalPath::from(vec![
+            Ordinal::with_unit(Kansuji::from(1_u8), "章", Vec::new()),
+            Ordinal::new(Kansuji::from(2_u8), OrdinalKind::第),
+        ]))
+    );
+    assert_eq!(
+        parse_ordinal_path("三番目二"),
+        Err(KansujiError::UnexpectedChar('二'))
+    );
+    assert_eq!(
+        parse_ordinal_path("第八条の二項"),
+        Err(KansujiError::UnexpectedChar('項'))
+    );
+}
+
+impl fmt::Display for OrdinalPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ordinal in self.要素.iter() {
+            write!(f, "{}", ordinal)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn check_ordinal_path_1() {
+    fn ordinal_path_test_function(s: &str) {
+        let path = OrdinalPath::try_from(s).unwrap();
+        assert_eq!(s, path.to_string());
+    }
+
+    let v = [
+        "第三条第二項第一号",
+        "第八条の二",
+        "第百二十条の三の二第一項",
+        "第一",
+        "三番目",
+        "十二番",
+    ];
+    v.iter().for_each(|s| ordinal_path_test_function(s));
+}
@@ -0,0 +1,446 @@
+//! serdeによる直列化と復元（`serde` featureで有効）
+//!
+//! [`Kansuji`]は既定では漢数字の文字列（`"三百二十"`）として直列化する。
+//! 復元では漢数字の文字列、算用数字の文字列（`"320"`・`"1.5"`）、整数、小数のいずれも受け付ける。
+//!
+//! 表現を変える場合は、`#[serde(with = "...")]`に次のモジュールを指定する。
+//!
+//! - [`kanji`]は漢数字の文字列（既定と同じ）
+//! - [`daiji`]は大字の文字列（`"参佰弐拾"`）
+//! - [`integer`]は整数（小数部がある場合はエラー）
+//! - [`decimal`]は十進の文字列（`"320"`・`"1.5"`）
+//!
+//! また、[`as_u64`]などを使うと、漢数字ではない整数型のフィールドを漢数字からも復元できる。
+//!
+//! bincodeやpostcardのように型の情報を持たない形式（`is_human_readable`が偽のもの）では、
+//! 漢数字の文字列は文字列としてのみ、[`integer`]と[`as_u64`]などは整数としてのみ復元する。
+//!
+//! ```
+//! use kansuji::Kansuji;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     price: Kansuji,
+//!     #[serde(with = "kansuji::serde::decimal")]
+//!     rate: Kansuji,
+//!     #[serde(with = "kansuji::serde::as_u64")]
+//!     count: u64,
+//! }
+//!
+//! let config: Config =
+//!     serde_json::from_str(r#"{"price": "三百二十", "rate": "一五分", "count": "三百"}"#).unwrap();
+//! assert_eq!(u128::from(config.price), 320);
+//! assert_eq!(config.count, 300);
+//! assert_eq!(
+//!     serde_json::to_string(&config).unwrap(),
+//!     r#"{"price":"三百二十","rate":"1.5","count":300}"#
+//! );
+//! ```
+
+use super::{
+    parse_kansuji_or_decimal, Kansuji, KansujiBuffer, KansujiError, KansujiFormat, KANSUJI_LIMIT,
+};
+use ::serde::de::{self, Deserializer, Visitor};
+use ::serde::ser::{self, Serializer};
+use ::serde::{Deserialize, Serialize};
+use core::fmt;
+
+impl Serialize for Kansuji {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        kanji::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Kansuji {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        kanji::deserialize(deserializer)
+    }
+}
+
+/// 整数として扱う値に小数部がある場合のエラーメッセージ
+const NOT_AN_INTEGER: &str = "not an integer";
+
+/// 漢数字で表せない大きさの値のエラーを作る
+fn too_large<E: de::Error>(v: impl fmt::Display) -> E {
+    E::custom(format_args!(
+        "{}: {} is not less than 10^24",
+        KansujiError::TooLarge,
+        v
+    ))
+}
+
+/// 漢数字の文字列・算用数字の文字列・整数・小数のいずれかから復元する
+struct KansujiVisitor;
+
+impl<'de> Visitor<'de> for KansujiVisitor {
+    type Value = Kansuji;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a kanji numeral, a decimal string or a non-negative number"
+        )
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Kansuji, E> {
+        Ok(Kansuji::from(v))
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Kansuji, E> {
+        if v >= KANSUJI_LIMIT {
+            return Err(too_large(v));
+        }
+        Ok(Kansuji::from(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Kansuji, E> {
+        if v < 0 {
+            return Err(E::custom(KansujiError::OutOfRange));
+        }
+        Ok(Kansuji::from(v as u64))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Kansuji, E> {
+        Kansuji::try_from_f64(v).map_err(|e| match e {
+            KansujiError::TooLarge => too_large(v),
+            e => E::custom(e),
+        })
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Kansuji, E> {
+        parse_kansuji_or_decimal(v).map_err(E::custom)
+    }
+}
+
+/// [`as_u64`]などで整数型のフィールドを復元する
+///
+/// 整数はそのまま受け取るため、10の24乗以上でも型に収まれば復元できる。
+struct IntegerVisitor;
+
+impl<'de> Visitor<'de> for IntegerVisitor {
+    type Value = u128;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a kanji numeral, a decimal string or a non-negative integer"
+        )
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<u128, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<u128, E> {
+        Ok(v)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<u128, E> {
+        u128::try_from(v).map_err(|_| E::custom(KansujiError::OutOfRange))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<u128, E> {
+        let kansuji = KansujiVisitor.visit_f64(v)?;
+        if kansuji.has_fraction() {
+            return Err(E::custom(NOT_AN_INTEGER));
+        }
+        Ok(kansuji.to_u128())
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<u128, E> {
+        let kansuji = KansujiVisitor.visit_str(v)?;
+        if kansuji.has_fraction() {
+            return Err(E::custom(NOT_AN_INTEGER));
+        }
+        Ok(kansuji.to_u128())
+    }
+}
+
+/// 書式を指定して漢数字の文字列として直列化する
+fn serialize_with<S: Serializer>(
+    kansuji: &Kansuji,
+    serializer: S,
+    format: &KansujiFormat,
+) -> Result<S::Ok, S::Error> {
+    let mut buffer = KansujiBuffer::new();
+    serializer.serialize_str(buffer.format(*kansuji, format))
+}
+
+/// 漢数字の文字列として直列化する（既定の表現）
+pub mod kanji {
+    use super::{serialize_with, Kansuji, KansujiFormat, KansujiVisitor};
+    use ::serde::{Deserializer, Serializer};
+
+    /// 漢数字の文字列として直列化する
+    pub fn serialize<S: Serializer>(kansuji: &Kansuji, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_with(kansuji, serializer, &KansujiFormat::default())
+    }
+
+    /// 漢数字の文字列・算用数字の文字列・整数・小数のいずれかから復元する
+    ///
+    /// 型の情報を持たない形式では文字列としてのみ復元する。
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Kansuji, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(KansujiVisitor)
+        } else {
+            deserializer.deserialize_str(KansujiVisitor)
+        }
+    }
+}
+
+/// 大字の文字列として直列化する
+pub mod daiji {
+    use super::{serialize_with, Kansuji, KansujiFormat};
+    use ::serde::Serializer;
+
+    /// 大字の文字列として直列化する
+    pub fn serialize<S: Serializer>(kansuji: &Kansuji, serializer: S) -> Result<S::Ok, S::Error> {
+        let format = KansujiFormat {
+            daiji: true,
+            ..KansujiFormat::default()
+        };
+        serialize_with(kansuji, serializer, &format)
+    }
+
+    pub use super::kanji::deserialize;
+}
+
+/// 整数として直列化する
+pub mod integer {
+    use super::{ser, Kansuji, KansujiVisitor, NOT_AN_INTEGER};
+    use ::serde::{Deserializer, Serializer};
+    use core::convert::TryFrom;
+
+    /// 整数として直列化する
+    ///
+    /// 小数部がある場合はエラーとする。u64に収まる場合はu64として書き出す。
+    /// 型の情報を持たない形式では常にu128として書き出す。
+    pub fn serialize<S: Serializer>(kansuji: &Kansuji, serializer: S) -> Result<S::Ok, S::Error> {
+        if kansuji.has_fraction() {
+            return Err(<S::Error as ser::Error>::custom(NOT_AN_INTEGER));
+        }
+        let n = kansuji.to_u128();
+        if !serializer.is_human_readable() {
+            return serializer.serialize_u128(n);
+        }
+        match u64::try_from(n) {
+            Ok(n) => serializer.serialize_u64(n),
+            Err(_) => serializer.serialize_u128(n),
+        }
+    }
+
+    /// 漢数字の文字列・算用数字の文字列・整数・小数のいずれかから復元する
+    ///
+    /// 型の情報を持たない形式ではu128としてのみ復元する。
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Kansuji, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(KansujiVisitor)
+        } else {
+            deserializer.deserialize_u128(KansujiVisitor)
+        }
+    }
+}
+
+/// 十進の文字列（`"320"`・`"1.5"`）として直列化する
+pub mod decimal {
+    use super::{ser, Kansuji, KansujiBuffer};
+    use ::serde::Serializer;
+
+    /// 十進の文字列として直列化する
+    pub fn serialize<S: Serializer>(kansuji: &Kansuji, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut buffer = KansujiBuffer::new();
+        kansuji
+            .write_decimal(&mut buffer)
+            .map_err(<S::Error as ser::Error>::custom)?;
+        serializer.serialize_str(buffer.as_str())
+    }
+
+    pub use super::kanji::deserialize;
+}
+
+/// 整数型のフィールドを漢数字からも復元できるようにするモジュールを作る
+macro_rules! integer_adapter {
+    ($name:ident, $t:ty, $doc:literal) => {
+        #[doc = $doc]
+        pub mod $name {
+            use super::{de, IntegerVisitor, KansujiError};
+            use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+            use core::convert::TryFrom;
+
+            /// 整数のまま直列化する
+            pub fn serialize<S: Serializer>(n: &$t, serializer: S) -> Result<S::Ok, S::Error> {
+                n.serialize(serializer)
+            }
+
+            /// 漢数字の文字列・算用数字の文字列・整数のいずれかから復元する
+            ///
+            /// 小数部がある場合や、型に収まらない場合はエラーとする。
+            /// 型の情報を持たない形式では整数としてのみ復元する。
+            pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<$t, D::Error> {
+                if !deserializer.is_human_readable() {
+                    return <$t>::deserialize(deserializer);
+                }
+                let n = deserializer.deserialize_any(IntegerVisitor)?;
+                <$t>::try_from(n)
+                    .map_err(|_| <D::Error as de::Error>::custom(KansujiError::TooLarge))
+            }
+        }
+    };
+}
+
+integer_adapter!(
+    as_u32,
+    u32,
+    "u32のフィールドを漢数字からも復元できるようにする"
+);
+integer_adapter!(
+    as_u64,
+    u64,
+    "u64のフィールドを漢数字からも復元できるようにする"
+);
+integer_adapter!(
+    as_u128,
+    u128,
+    "u128のフィールドを漢数字からも復元できるようにする"
+);
+
+#[test]
+fn check_serde_1() {
+    let kansuji: Kansuji = serde_json::from_str(r#""三百二十""#).unwrap();
+    assert_eq!(kansuji, Kansuji::from(320_u16));
+    let kansuji: Kansuji = serde_json::from_str(r#""320""#).unwrap();
+    assert_eq!(kansuji, Kansuji::from(320_u16));
+    let kansuji: Kansuji = serde_json::from_str("320").unwrap();
+    assert_eq!(kansuji, Kansuji::from(320_u16));
+    let kansuji: Kansuji = serde_json::from_str("1.5").unwrap();
+    assert_eq!(kansuji, Kansuji::from(1.5));
+    assert_eq!(
+        serde_json::to_string(&Kansuji::from(35000_u32)).unwrap(),
+        r#""三万五千""#
+    );
+    assert!(serde_json::from_str::<Kansuji>(r#""三百円""#).is_err());
+    assert!(serde_json::from_str::<Kansuji>(r#""""#).is_err());
+    assert!(serde_json::from_str::<Kansuji>("-1").is_err());
+    assert!(serde_json::from_str::<Kansuji>("true").is_err());
+}
+
+#[test]
+fn check_serde_2() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Amounts {
+        #[serde(with = "daiji")]
+        daiji: Kansuji,
+        #[serde(with = "integer")]
+        integer: Kansuji,
+        #[serde(with = "decimal")]
+        decimal: Kansuji,
+        #[serde(with = "as_u64")]
+        count: u64,
+    }
+
+    let amounts: Amounts = serde_json::from_str(
+        r#"{"daiji": "壱萬", "integer": "三百", "decimal": "一二分五厘", "count": "三百"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        amounts,
+        Amounts {
+            daiji: Kansuji::from(10000_u16),
+            integer: Kansuji::from(300_u16),
+            decimal: Kansuji::from(1.25),
+            count: 300,
+        }
+    );
+    let s = serde_json::to_string(&amounts).unwrap();
+    assert_eq!(
+        s,
+        r#"{"daiji":"壱萬","integer":300,"decimal":"1.25","count":300}"#
+    );
+    assert_eq!(serde_json::from_str::<Amounts>(&s).unwrap(), amounts);
+    let amounts = Amounts {
+        integer: Kansuji::from(1.5),
+        ..amounts
+    };
+    assert!(serde_json::to_string(&amounts).is_err());
+}
+
+#[test]
+fn check_serde_3() {
+    #[derive(Deserialize)]
+    struct Count {
+        #[serde(with = "as_u32")]
+        count: u32,
+    }
+
+    let count: Count = serde_json::from_str(r#"{"count": "五千"}"#).unwrap();
+    assert_eq!(count.count, 5000);
+    let count: Count = serde_json::from_str(r#"{"count": 12}"#).unwrap();
+    assert_eq!(count.count, 12);
+    assert!(serde_json::from_str::<Count>(r#"{"count": "一兆"}"#).is_err());
+    assert!(serde_json::from_str::<Count>(r#"{"count": "一五分"}"#).is_err());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn check_serde_4() {
+    use ::serde::de::value::{Error, U128Deserializer};
+    use ::serde::de::IntoDeserializer;
+    use alloc::string::ToString;
+
+    let e = serde_json::from_str::<Kansuji>("1e30").unwrap_err();
+    assert!(e.to_string().starts_with("too large: "));
+    assert!(serde_json::from_str::<Kansuji>("-1.5").is_err());
+    let n = 10_u128.pow(30);
+    let deserializer: U128Deserializer<Error> = n.into_deserializer();
+    assert_eq!(as_u128::deserialize(deserializer), Ok(n));
+    let deserializer: U128Deserializer<Error> = n.into_deserializer();
+    let e = Kansuji::deserialize(deserializer).unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        "too large: 1000000000000000000000000000000 is not less than 10^24"
+    );
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Count {
+        #[serde(with = "integer")]
+        integer: Kansuji,
+        #[serde(with = "as_u32")]
+        count: u32,
+    }
+    let count = Count {
+        integer: Kansuji::from(1.5),
+        count: 0,
+    };
+    let e = serde_json::to_string(&count).unwrap_err();
+    assert_eq!(e.to_string(), "not an integer");
+    let e = serde_json::from_str::<Count>(r#"{"integer": 1, "count": "一五分"}"#).unwrap_err();
+    assert!(e.to_string().starts_with("not an integer"));
+}
+
+#[test]
+fn check_serde_5() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Amounts {
+        kanji: Kansuji,
+        #[serde(with = "daiji")]
+        daiji: Kansuji,
+        #[serde(with = "integer")]
+        integer: Kansuji,
+        #[serde(with = "decimal")]
+        decimal: Kansuji,
+        #[serde(with = "as_u64")]
+        count: u64,
+    }
+
+    // bincodeは型の情報を持たない
+    let amounts = Amounts {
+        kanji: Kansuji::from(320_u16),
+        daiji: Kansuji::from(10000_u16),
+        integer: Kansuji::from(300_u16),
+        decimal: Kansuji::from(1.25),
+        count: 300,
+    };
+    let bytes = bincode::serialize(&amounts).unwrap();
+    assert_eq!(bincode::deserialize::<Amounts>(&bytes).unwrap(), amounts);
+}
@@ -0,0 +1,379 @@
+//! 尺貫法の単位の付いた量（五尺三寸・三坪・二升五合など）の解析とSI単位への変換を行う
+//!
+//! 分・厘・合・勺のように複数の量で使われる単位は、一緒に使われている単位から量の種類を決める。
+//!
+//! ```
+//! use kansuji::{Shakkan, ShakkanShurui};
+//!
+//! let shakkan = Shakkan::try_from("五尺三寸").unwrap();
+//! assert_eq!(shakkan.kind(), ShakkanShurui::長さ);
+//! assert!((shakkan.to_si() - 1.606).abs() < 0.001);
+//!
+//! let shakkan = Shakkan::try_from("二升五合").unwrap();
+//! assert_eq!(shakkan.kind(), ShakkanShurui::体積);
+//! assert_eq!(shakkan.in_unit("合"), Some(25.0));
+//!
+//! let shakkan = Shakkan::parse_with_kind("三分", ShakkanShurui::質量).unwrap();
+//! assert!((shakkan.to_si() - 0.001125).abs() < 1e-9);
+//! ```
+
+use super::{is_numeral_char, parse_integer, Kansuji, KansujiError, KANSUJI_LIMIT};
+use alloc::string::String;
+#[cfg(test)]
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::convert::{From, TryFrom};
+use core::fmt;
+
+/// 尺貫法で表す量の種類
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum ShakkanShurui {
+    /// 長さ（尺・寸・分・厘・丈・間）
+    長さ,
+    /// 面積（町・反・畝・坪・歩・合・勺）
+    面積,
+    /// 体積（石・斗・升・合・勺）
+    体積,
+    /// 質量（貫・匁・分・厘）
+    質量,
+}
+
+impl ShakkanShurui {
+    /// 変換先のSI単位の記号を返す
+    pub fn si_unit(self) -> &'static str {
+        match self {
+            ShakkanShurui::長さ => "m",
+            ShakkanShurui::面積 => "m²",
+            ShakkanShurui::体積 => "m³",
+            ShakkanShurui::質量 => "kg",
+        }
+    }
+
+    /// 最小の単位一つ分をSI単位で返す
+    fn base_to_si(self) -> f64 {
+        match self {
+            // 一尺は10/33m
+            ShakkanShurui::長さ => 10.0 / 33.0 / 1000.0,
+            // 一坪は一間四方
+            ShakkanShurui::面積 => 400.0 / 121.0 / 100.0,
+            // 一升は64.827立方寸（64827立方分）
+            ShakkanShurui::体積 => 2401.0 / 1331000.0 / 100.0,
+            // 一匁は3.75g
+            ShakkanShurui::質量 => 0.00375 / 100.0,
+        }
+    }
+}
+
+/// 単位の表記・量の種類・最小の単位で数えた大きさ・表示に使うかどうか
+const SHAKKAN_UNITS: [(&str, ShakkanShurui, u128, bool); 22] = [
+    ("丈", ShakkanShurui::長さ, 10000, false),
+    ("間", ShakkanShurui::長さ, 6000, false),
+    ("尺", ShakkanShurui::長さ, 1000, true),
+    ("寸", ShakkanShurui::長さ, 100, true),
+    ("分", ShakkanShurui::長さ, 10, true),
+    ("厘", ShakkanShurui::長さ, 1, true),
+    ("町", ShakkanShurui::面積, 300000, true),
+    ("反", ShakkanShurui::面積, 30000, true),
+    ("畝", ShakkanShurui::面積, 3000, true),
+    ("坪", ShakkanShurui::面積, 100, true),
+    ("歩", ShakkanShurui::面積, 100, false),
+    ("合", ShakkanShurui::面積, 10, true),
+    ("勺", ShakkanShurui::面積, 1, true),
+    ("石", ShakkanShurui::体積, 10000, true),
+    ("斗", ShakkanShurui::体積, 1000, true),
+    ("升", ShakkanShurui::体積, 100, true),
+    ("合", ShakkanShurui::体積, 10, true),
+    ("勺", ShakkanShurui::体積, 1, true),
+    ("貫", ShakkanShurui::質量, 100000, true),
+    ("匁", ShakkanShurui::質量, 100, true),
+    ("分", ShakkanShurui::質量, 10, true),
+    ("厘", ShakkanShurui::質量, 1, true),
+];
+
+const SHAKKAN_KINDS: [ShakkanShurui; 4] = [
+    ShakkanShurui::長さ,
+    ShakkanShurui::面積,
+    ShakkanShurui::体積,
+    ShakkanShurui::質量,
+];
+
+/// 単位の大きさを最小の単位で数えて返す
+fn unit_amount(unit: &str, kind: ShakkanShurui) -> Option<u128> {
+    SHAKKAN_UNITS
+        .iter()
+        .find(|(u, k, _, _)| *u == unit && *k == kind)
+        .map(|(_, _, n, _)| *n)
+}
+
+/// 尺貫法の単位の付いた量
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct Shakkan {
+    種類: ShakkanShurui,
+    /// 長さは厘、面積と体積は勺、質量は厘で数えた量
+    量: u128,
+}
+
+impl Shakkan {
+    /// 量の種類を決めて読む
+    ///
+    /// `三分`のように単位だけでは量の種類が決まらないものに使う。
+    pub fn parse_with_kind(s: &str, kind: ShakkanShurui) -> Result<Self, KansujiError> {
+        let terms = split_terms(s)?;
+        to_shakkan(&terms, kind)
+    }
+
+    /// 量の種類を返す
+    pub fn kind(&self) -> ShakkanShurui {
+        self.種類
+    }
+
+    /// 同じ種類の単位で数えた量を返す
+    ///
+    /// 単位がこの量の種類で使われないものの場合は`None`を返す。
+    pub fn in_unit(&self, unit: &str) -> Option<f64> {
+        unit_amount(unit, self.種類).map(|n| self.量 as f64 / n as f64)
+    }
+
+    /// SI単位（m・m²・m³・kg）に変換する
+    pub fn to_si(&self) -> f64 {
+        self.量 as f64 * self.種類.base_to_si()
+    }
+}
+
+impl TryFrom<String> for Shakkan {
+    type Error = KansujiError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_shakkan(&value)
+    }
+}
+
+impl TryFrom<&String> for Shakkan {
+    type Error = KansujiError;
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        parse_shakkan(value)
+    }
+}
+
+impl TryFrom<&str> for Shakkan {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_shakkan(value)
+    }
+}
+
+/// 数と単位の組に分ける
+fn split_terms(s: &str) -> Result<Vec<(u128, &'static str)>, KansujiError> {
+    let mut terms = Vec::new();
+    let mut rest = s;
+    while !rest.is_empty() {
+        let i = rest
+            .char_indices()
+            .find(|(_, c)| !is_numeral_char(*c))
+            .map_or(rest.len(), |(i, _)| i);
+        let (n, r) = rest.split_at(i);
+        let c = match r.chars().next() {
+            Some(c) => c,
+            None => return Err(KansujiError::UnexpectedEnd),
+        };
+        if n.is_empty() {
+            return Err(KansujiError::UnexpectedChar(c));
+        }
+        let unit = SHAKKAN_UNITS
+            .iter()
+            .map(|(u, _, _, _)| *u)
+            .find(|u| r.starts_with(u))
+            .ok_or(KansujiError::UnexpectedChar(c))?;
+        terms.push((parse_integer(n)?, unit));
+        rest = &r[unit.len()..];
+    }
+    if terms.is_empty() {
+        return Err(KansujiError::UnexpectedEnd);
+    }
+    Ok(terms)
+}
+
+/// 単位が大きい順に並んでいることを確かめて量を求める
+fn to_shakkan(terms: &[(u128, &str)], kind: ShakkanShurui) -> Result<Shakkan, KansujiError> {
+    let mut total: u128 = 0;
+    let mut prev = None;
+    for (n, unit) in terms.iter() {
+        let amount = unit_amount(unit, kind).ok_or(KansujiError::ParseError)?;
+        if prev.map_or(false, |p| p <= amount) {
+            return Err(KansujiError::ParseError);
+        }
+        prev = Some(amount);
+        total = n
+            .checked_mul(amount)
+            .and_then(|n| total.checked_add(n))
+            .ok_or(KansujiError::TooLarge)?;
+    }
+    // 表示に使う最も大きい単位の数が漢数字で表せること
+    let largest = SHAKKAN_UNITS
+        .iter()
+        .find(|(_, k, _, display)| *k == kind && *display)
+        .map_or(1, |(_, _, n, _)| *n);
+    if total / largest >= KANSUJI_LIMIT {
+        return Err(KansujiError::TooLarge);
+    }
+    Ok(Shakkan {
+        種類: kind,
+        量: total,
+    })
+}
+
+fn parse_shakkan(s: &str) -> Result<Shakkan, KansujiError> {
+    let terms = split_terms(s)?;
+    let mut kinds = SHAKKAN_KINDS
+        .iter()
+        .filter(|k| terms.iter().all(|(_, u)| unit_amount(u, **k).is_some()));
+    match (kinds.next(), kinds.next()) {
+        (Some(kind), None) => to_shakkan(&terms, *kind),
+        // 量の種類が決まらない
+        _ => Err(KansujiError::ParseError),
+    }
+}
+
+#[test]
+fn check_parse_shakkan_1() {
+    let v = [
+        ("五尺三寸", ShakkanShurui::長さ, 5300),
+        ("一尺五分", ShakkanShurui::長さ, 1050),
+        ("二間", ShakkanShurui::長さ, 12000),
+        ("一丈二尺", ShakkanShurui::長さ, 12000),
+        ("三坪", ShakkanShurui::面積, 300),
+        ("一町二反三畝四歩", ShakkanShurui::面積, 369400),
+        ("三坪五合", ShakkanShurui::面積, 350),
+        ("二升五合", ShakkanShurui::体積, 250),
+        ("一石", ShakkanShurui::体積, 10000),
+        ("百匁", ShakkanShurui::質量, 10000),
+        ("一貫二百匁", ShakkanShurui::質量, 120000),
+        ("三匁五分", ShakkanShurui::質量, 350),
+        ("12坪", ShakkanShurui::面積, 1200),
+    ];
+    for (s, kind, n) in v.iter() {
+        assert_eq!(
+            parse_shakkan(s),
+            Ok(Shakkan {
+                種類: *kind,
+                量: *n
+            })
+        );
+    }
+}
+
+#[test]
+fn check_parse_shakkan_2() {
+    assert_eq!(parse_shakkan(""), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_shakkan("五"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_shakkan("尺"), Err(KansujiError::UnexpectedChar('尺')));
+    assert_eq!(
+        parse_shakkan("五米"),
+        Err(KansujiError::UnexpectedChar('米'))
+    );
+    // 分だけでは長さか質量か決まらない
+    assert_eq!(parse_shakkan("三分"), Err(KansujiError::ParseError));
+    assert_eq!(parse_shakkan("三寸五尺"), Err(KansujiError::ParseError));
+    assert_eq!(parse_shakkan("三尺二匁"), Err(KansujiError::ParseError));
+    assert_eq!(
+        Shakkan::parse_with_kind("三分", ShakkanShurui::長さ),
+        Ok(Shakkan {
+            種類: ShakkanShurui::長さ,
+            量: 30
+        })
+    );
+    assert_eq!(
+        Shakkan::parse_with_kind("三合", ShakkanShurui::質量),
+        Err(KansujiError::ParseError)
+    );
+    assert_eq!(
+        parse_shakkan("1000000000000000000000000000尺"),
+        Err(KansujiError::TooLarge)
+    );
+    assert_eq!(
+        parse_shakkan("1000000000000000000000000尺"),
+        Err(KansujiError::TooLarge)
+    );
+    assert_eq!(
+        parse_shakkan("200000000000000000000000間").map(|s| s.to_string()),
+        Err(KansujiError::TooLarge)
+    );
+    assert_eq!(
+        parse_shakkan("999999999999999999999999尺").map(|s| s.to_string()),
+        Ok("九千九百九十九垓九千九百九十九京九千九百九十九兆九千九百九十九億九千九百九十九万九千九百九十九尺".to_string())
+    );
+}
+
+#[test]
+fn check_shakkan_to_si_1() {
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < b * 1e-9
+    }
+    assert!(close(
+        Shakkan::try_from("一尺").unwrap().to_si(),
+        10.0 / 33.0
+    ));
+    assert!(close(
+        Shakkan::try_from("一間").unwrap().to_si(),
+        60.0 / 33.0
+    ));
+    assert!(close(
+        Shakkan::try_from("一坪").unwrap().to_si(),
+        400.0 / 121.0
+    ));
+    assert!(close(
+        Shakkan::try_from("一反").unwrap().to_si(),
+        120000.0 / 121.0
+    ));
+    assert!(close(
+        Shakkan::try_from("一升").unwrap().to_si(),
+        2401.0 / 1331000.0
+    ));
+    assert!(close(Shakkan::try_from("一貫").unwrap().to_si(), 3.75));
+    let shakkan = Shakkan::try_from("二間").unwrap();
+    assert_eq!(shakkan.in_unit("尺"), Some(12.0));
+    assert_eq!(shakkan.in_unit("坪"), None);
+}
+
+impl fmt::Display for Shakkan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut units = SHAKKAN_UNITS
+            .iter()
+            .filter(|(_, k, _, display)| *k == self.種類 && *display)
+            .peekable();
+        // 零は量の種類で一番大きい単位を付ける（零尺・零町など）
+        if self.量 == 0 {
+            if let Some((unit, _, _, _)) = units.peek() {
+                write!(f, "零{}", unit)?;
+            }
+            return Ok(());
+        }
+        let mut rest = self.量;
+        for (unit, _, n, _) in units {
+            if rest >= *n {
+                write!(f, "{}{}", Kansuji::from(rest / n), unit)?;
+                rest %= n;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn check_shakkan_1() {
+    fn shakkan_test_function((s, t): &(&str, &str)) {
+        assert_eq!(Shakkan::try_from(*s).unwrap().to_string(), *t);
+    }
+
+    let v = [
+        ("五尺三寸", "五尺三寸"),
+        ("二間", "十二尺"),
+        ("一町二反三畝四歩", "一町二反三畝四坪"),
+        ("二升五合", "二升五合"),
+        ("一貫二百匁", "一貫二百匁"),
+        ("零坪", "零町"),
+        ("零尺", "零尺"),
+        ("零升", "零石"),
+        ("零匁", "零貫"),
+    ];
+    v.iter().for_each(shakkan_test_function);
+}
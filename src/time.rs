@@ -0,0 +1,612 @@
+//! 時刻と期間の解析と変換を行う
+//!
+//! 時刻や期間では`分`は時間の単位であり、[`Kansuji`]の`分`（十分の一）とは意味が異なる。
+//! そのため、数の部分だけを切り出して[`Kansuji`]として読む。
+//!
+//! ```
+//! use kansuji::{Gozengogo, Jikoku, Kikan};
+//!
+//! let jikoku = Jikoku::try_from("午後三時十五分").unwrap();
+//! assert_eq!(jikoku.gozengogo(), Some(Gozengogo::午後));
+//! assert_eq!(jikoku.hour24(), 15);
+//! assert_eq!(jikoku.minute(), 15);
+//!
+//! let kikan = Kikan::try_from("二時間半").unwrap();
+//! assert_eq!(kikan.hours(), 2);
+//! assert_eq!(kikan.minutes(), 30);
+//! assert_eq!(kikan.to_string(), "二時間三十分");
+//! ```
+
+use super::{parse_integer, Kansuji, KansujiError};
+use alloc::string::String;
+#[cfg(test)]
+use alloc::string::ToString;
+use core::convert::{From, TryFrom};
+use core::fmt;
+use core::time::Duration;
+
+/// 午前と午後
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Gozengogo {
+    午前,
+    午後,
+}
+
+/// 時刻
+///
+/// 午前・午後が指定された場合、時は0から12までとする。
+/// 午前十二時は0時（深夜）、午後十二時は12時（正午）として扱う。
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct Jikoku {
+    午前午後: Option<Gozengogo>,
+    時: u8,
+    分: u8,
+    秒: u8,
+}
+
+impl Jikoku {
+    /// 時刻を作る
+    pub fn new(
+        gozengogo: Option<Gozengogo>,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Result<Self, KansujiError> {
+        let max_hour = if gozengogo.is_some() { 12 } else { 24 };
+        if hour > max_hour || minute >= 60 || second >= 60 {
+            return Err(KansujiError::OutOfRange);
+        }
+        Ok(Jikoku {
+            午前午後: gozengogo,
+            時: hour,
+            分: minute,
+            秒: second,
+        })
+    }
+
+    /// 午前・午後を返す
+    pub fn gozengogo(&self) -> Option<Gozengogo> {
+        self.午前午後
+    }
+
+    /// 書かれたままの時を返す
+    pub fn hour(&self) -> u8 {
+        self.時
+    }
+
+    /// 24時間制での時（0から23まで）を返す
+    ///
+    /// 午前十二時と二十四時は0時、午後十二時は12時とする。
+    pub fn hour24(&self) -> u8 {
+        match (self.午前午後, self.時) {
+            (Some(Gozengogo::午前), 12) | (None, 24) => 0,
+            (Some(Gozengogo::午後), 12) => 12,
+            (Some(Gozengogo::午後), hour) => hour + 12,
+            (_, hour) => hour,
+        }
+    }
+
+    /// 分を返す
+    pub fn minute(&self) -> u8 {
+        self.分
+    }
+
+    /// 秒を返す
+    pub fn second(&self) -> u8 {
+        self.秒
+    }
+}
+
+impl TryFrom<String> for Jikoku {
+    type Error = KansujiError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_jikoku(&value)
+    }
+}
+
+impl TryFrom<&String> for Jikoku {
+    type Error = KansujiError;
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        parse_jikoku(value)
+    }
+}
+
+impl TryFrom<&str> for Jikoku {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_jikoku(value)
+    }
+}
+
+/// 数とその後ろに続く単位を読み、数と単位と残りの文字列を返す
+///
+/// 単位は長いものを先に並べておく。
+fn split_unit<'a>(
+    s: &'a str,
+    units: &[&'static str],
+) -> Result<(u128, &'static str, &'a str), KansujiError> {
+    for (i, _) in s.char_indices() {
+        if let Some(unit) = units.iter().find(|unit| s[i..].starts_with(**unit)) {
+            let n = parse_integer(&s[..i])?;
+            return Ok((n, unit, &s[i + unit.len()..]));
+        }
+    }
+    parse_integer(s)?;
+    Err(KansujiError::UnexpectedEnd)
+}
+
+fn to_u8(n: u128) -> Result<u8, KansujiError> {
+    u8::try_from(n).map_err(|_| KansujiError::OutOfRange)
+}
+
+fn parse_jikoku(s: &str) -> Result<Jikoku, KansujiError> {
+    let (gozengogo, s) = if let Some(s) = s.strip_prefix("午前") {
+        (Some(Gozengogo::午前), s)
+    } else if let Some(s) = s.strip_prefix("午後") {
+        (Some(Gozengogo::午後), s)
+    } else {
+        (None, s)
+    };
+    let (hour, _, mut rest) = split_unit(s, &["時"])?;
+    let mut minute = 0;
+    let mut second = 0;
+    if let Some(r) = rest.strip_prefix('半') {
+        minute = 30;
+        rest = r;
+    } else if !rest.is_empty() {
+        let (n, unit, r) = split_unit(rest, &["分", "秒"])?;
+        if unit == "分" {
+            minute = n;
+            rest = r;
+            if !rest.is_empty() {
+                let (n, _, r) = split_unit(rest, &["秒"])?;
+                second = n;
+                rest = r;
+            }
+        } else {
+            second = n;
+            rest = r;
+        }
+    }
+    if let Some(c) = rest.chars().next() {
+        return Err(KansujiError::UnexpectedChar(c));
+    }
+    Jikoku::new(gozengogo, to_u8(hour)?, to_u8(minute)?, to_u8(second)?)
+}
+
+#[test]
+fn check_parse_jikoku_1() {
+    assert_eq!(
+        parse_jikoku("午後三時十五分"),
+        Ok(Jikoku {
+            午前午後: Some(Gozengogo::午後),
+            時: 3,
+            分: 15,
+            秒: 0,
+        })
+    );
+    assert_eq!(
+        parse_jikoku("午前十時半"),
+        Jikoku::new(Some(Gozengogo::午前), 10, 30, 0)
+    );
+    assert_eq!(parse_jikoku("十五時"), Jikoku::new(None, 15, 0, 0));
+    assert_eq!(
+        parse_jikoku("二十三時五十九分五十九秒"),
+        Jikoku::new(None, 23, 59, 59)
+    );
+    assert_eq!(parse_jikoku("9時5秒"), Jikoku::new(None, 9, 0, 5));
+}
+
+#[test]
+fn check_parse_jikoku_2() {
+    assert_eq!(parse_jikoku("午後十三時"), Err(KansujiError::OutOfRange));
+    assert_eq!(parse_jikoku("三時六十分"), Err(KansujiError::OutOfRange));
+    assert_eq!(
+        parse_jikoku("三時間"),
+        Err(KansujiError::UnexpectedChar('間'))
+    );
+    assert_eq!(parse_jikoku("三"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(
+        parse_jikoku("三時半過ぎ"),
+        Err(KansujiError::UnexpectedChar('過'))
+    );
+}
+
+impl fmt::Display for Jikoku {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.午前午後 {
+            Some(Gozengogo::午前) => write!(f, "午前")?,
+            Some(Gozengogo::午後) => write!(f, "午後")?,
+            None => (),
+        }
+        write!(f, "{}時", Kansuji::from(self.時))?;
+        if self.分 != 0 {
+            write!(f, "{}分", Kansuji::from(self.分))?;
+        }
+        if self.秒 != 0 {
+            write!(f, "{}秒", Kansuji::from(self.秒))?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn check_jikoku_1() {
+    fn jikoku_test_function(s: &str) {
+        let jikoku = Jikoku::try_from(s).unwrap();
+        assert_eq!(s, jikoku.to_string());
+    }
+
+    let v = ["午後三時十五分", "午前零時", "十五時三十分十秒", "九時五秒"];
+    v.iter().for_each(|s| jikoku_test_function(s));
+    assert_eq!(Jikoku::try_from("午後十二時").unwrap().hour24(), 12);
+    assert_eq!(Jikoku::try_from("午前十二時").unwrap().hour24(), 0);
+    assert_eq!(Jikoku::try_from("午後十一時").unwrap().hour24(), 23);
+    assert_eq!(Jikoku::try_from("二十四時").unwrap().hour24(), 0);
+    assert_eq!(Jikoku::try_from("十二時").unwrap().hour24(), 12);
+}
+
+/// 期間
+///
+/// 年・月・週・日・時間・分・秒のそれぞれの量を保持する。
+/// `半`は直前の単位の半分として、一つ下の単位に加える。
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub struct Kikan {
+    年: u32,
+    月: u32,
+    週: u32,
+    日: u32,
+    時: u32,
+    分: u32,
+    秒: u32,
+}
+
+impl Kikan {
+    /// 年数を返す
+    pub fn years(&self) -> u32 {
+        self.年
+    }
+
+    /// 月数を返す
+    pub fn months(&self) -> u32 {
+        self.月
+    }
+
+    /// 週数を返す
+    pub fn weeks(&self) -> u32 {
+        self.週
+    }
+
+    /// 日数を返す
+    pub fn days(&self) -> u32 {
+        self.日
+    }
+
+    /// 時間数を返す
+    pub fn hours(&self) -> u32 {
+        self.時
+    }
+
+    /// 分数を返す
+    pub fn minutes(&self) -> u32 {
+        self.分
+    }
+
+    /// 秒数を返す
+    pub fn seconds(&self) -> u32 {
+        self.秒
+    }
+}
+
+impl TryFrom<String> for Kikan {
+    type Error = KansujiError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_kikan(&value)
+    }
+}
+
+impl TryFrom<&String> for Kikan {
+    type Error = KansujiError;
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        parse_kikan(value)
+    }
+}
+
+impl TryFrom<&str> for Kikan {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_kikan(value)
+    }
+}
+
+const KIKAN_UNITS: [&str; 20] = [
+    "ヶ月間",
+    "か月間",
+    "カ月間",
+    "ヵ月間",
+    "箇月間",
+    "ヶ月",
+    "か月",
+    "カ月",
+    "ヵ月",
+    "箇月",
+    "年間",
+    "週間",
+    "日間",
+    "時間",
+    "分間",
+    "秒間",
+    "年",
+    "日",
+    "分",
+    "秒",
+];
+
+fn parse_kikan(s: &str) -> Result<Kikan, KansujiError> {
+    let mut kikan = Kikan::default();
+    // 大きい単位から順に並んでいることを確かめるための、直前の単位の順位
+    let mut keta = 8_u8;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let (n, unit, r) = split_unit(rest, &KIKAN_UNITS)?;
+        let n = u32::try_from(n).map_err(|_| KansujiError::TooLarge)?;
+        let (k, half) = match unit.chars().next() {
+            Some('年') => {
+                kikan.年 = n;
+                (
+                    7,
+                    Kikan {
+                        月: 6,
+                        ..Kikan::default()
+                    },
+                )
+            }
+            Some('週') => {
+                kikan.週 = n;
+                (
+                    5,
+                    Kikan {
+                        日: 3,
+                        時: 12,
+                        ..Kikan::default()
+                    },
+                )
+            }
+            Some('日') => {
+                kikan.日 = n;
+                (
+                    4,
+                    Kikan {
+                        時: 12,
+                        ..Kikan::default()
+                    },
+                )
+            }
+            Some('時') => {
+                kikan.時 = n;
+                (
+                    3,
+                    Kikan {
+                        分: 30,
+                        ..Kikan::default()
+                    },
+                )
+            }
+            Some('分') => {
+                kikan.分 = n;
+                (
+                    2,
+                    Kikan {
+                        秒: 30,
+                        ..Kikan::default()
+                    },
+                )
+            }
+            Some('秒') => {
+                kikan.秒 = n;
+                (1, Kikan::default())
+            }
+            _ => {
+                kikan.月 = n;
+                (
+                    6,
+                    Kikan {
+                        日: 15,
+                        ..Kikan::default()
+                    },
+                )
+            }
+        };
+        if k >= keta {
+            return Err(KansujiError::UnexpectedChar(unit.chars().next().unwrap()));
+        }
+        keta = k;
+        rest = r;
+        if let Some(r) = rest.strip_prefix('半') {
+            if half == Kikan::default() {
+                return Err(KansujiError::UnexpectedChar('半'));
+            }
+            kikan = Kikan {
+                月: kikan.月 + half.月,
+                日: kikan.日 + half.日,
+                時: kikan.時 + half.時,
+                分: kikan.分 + half.分,
+                秒: kikan.秒 + half.秒,
+                ..kikan
+            };
+            rest = r;
+            // 半で補った単位（週の半は日と時）を続けて書くことはできない
+            keta = if half.秒 != 0 {
+                1
+            } else if half.分 != 0 {
+                2
+            } else if half.時 != 0 {
+                3
+            } else if half.日 != 0 {
+                4
+            } else {
+                6
+            };
+        }
+    }
+    if keta == 8 {
+        return Err(KansujiError::UnexpectedEnd);
+    }
+    Ok(kikan)
+}
+
+#[test]
+fn check_parse_kikan_1() {
+    assert_eq!(
+        parse_kikan("二時間半"),
+        Ok(Kikan {
+            時: 2,
+            分: 30,
+            ..Kikan::default()
+        })
+    );
+    assert_eq!(
+        parse_kikan("一週間半三十分"),
+        Ok(Kikan {
+            週: 1,
+            日: 3,
+            時: 12,
+            分: 30,
+            ..Kikan::default()
+        })
+    );
+    assert_eq!(
+        parse_kikan("一時間三十分"),
+        Ok(Kikan {
+            時: 1,
+            分: 30,
+            ..Kikan::default()
+        })
+    );
+    assert_eq!(
+        parse_kikan("三ヶ月"),
+        Ok(Kikan {
+            月: 3,
+            ..Kikan::default()
+        })
+    );
+    assert_eq!(
+        parse_kikan("一年半"),
+        Ok(Kikan {
+            年: 1,
+            月: 6,
+            ..Kikan::default()
+        })
+    );
+    assert_eq!(
+        parse_kikan("二週間三日"),
+        Ok(Kikan {
+            週: 2,
+            日: 3,
+            ..Kikan::default()
+        })
+    );
+    assert_eq!(
+        parse_kikan("十日間"),
+        Ok(Kikan {
+            日: 10,
+            ..Kikan::default()
+        })
+    );
+    assert_eq!(
+        parse_kikan("五分間"),
+        Ok(Kikan {
+            分: 5,
+            ..Kikan::default()
+        })
+    );
+}
+
+#[test]
+fn check_parse_kikan_2() {
+    assert_eq!(parse_kikan(""), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_kikan("三"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(
+        parse_kikan("三分二時間"),
+        Err(KansujiError::UnexpectedChar('時'))
+    );
+    assert_eq!(
+        parse_kikan("一時間半十分"),
+        Err(KansujiError::UnexpectedChar('分'))
+    );
+    assert_eq!(
+        parse_kikan("一週間半三時間"),
+        Err(KansujiError::UnexpectedChar('時'))
+    );
+    assert_eq!(
+        parse_kikan("一週間半三日"),
+        Err(KansujiError::UnexpectedChar('日'))
+    );
+    assert_eq!(
+        parse_kikan("三秒半"),
+        Err(KansujiError::UnexpectedChar('半'))
+    );
+    assert_eq!(parse_kikan("三時"), Err(KansujiError::UnexpectedChar('時')));
+}
+
+impl fmt::Display for Kikan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == Kikan::default() {
+            return write!(f, "零秒");
+        }
+        for (n, unit) in [
+            (self.年, "年"),
+            (self.月, "ヶ月"),
+            (self.週, "週間"),
+            (self.日, "日"),
+            (self.時, "時間"),
+            (self.分, "分"),
+            (self.秒, "秒"),
+        ] {
+            if n != 0 {
+                write!(f, "{}{}", Kansuji::from(n), unit)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<Kikan> for Duration {
+    type Error = KansujiError;
+    /// 年と月は長さが一定でないため、含まれている場合はエラーとする
+    fn try_from(value: Kikan) -> Result<Self, Self::Error> {
+        if value.年 != 0 || value.月 != 0 {
+            return Err(KansujiError::OutOfRange);
+        }
+        let secs = (((value.週 as u64 * 7 + value.日 as u64) * 24 + value.時 as u64) * 60
+            + value.分 as u64)
+            * 60
+            + value.秒 as u64;
+        Ok(Duration::from_secs(secs))
+    }
+}
+
+#[test]
+fn check_kikan_1() {
+    fn kikan_test_function(s: &str) {
+        let kikan = Kikan::try_from(s).unwrap();
+        assert_eq!(s, kikan.to_string());
+    }
+
+    let v = [
+        "二時間三十分",
+        "一年六ヶ月",
+        "三週間",
+        "十日十二時間",
+        "五分三十秒",
+    ];
+    v.iter().for_each(|s| kikan_test_function(s));
+}
+
+#[test]
+fn check_kikan_2() {
+    let kikan = Kikan::try_from("一日二時間半").unwrap();
+    assert_eq!(Duration::try_from(kikan), Ok(Duration::from_secs(95400)));
+    let kikan = Kikan::try_from("一ヶ月").unwrap();
+    assert_eq!(Duration::try_from(kikan), Err(KansujiError::OutOfRange));
+}
@@ -0,0 +1,136 @@
+//! WebAssemblyから使うための関数（`wasm` featureで有効）
+//!
+//! `wasm-bindgen`でJavaScriptの関数として公開する。TypeScriptの型定義も`wasm-bindgen`が生成する。
+//!
+//! ```sh
+//! cargo rustc --lib --crate-type cdylib --release --target wasm32-unknown-unknown --features wasm
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/kansuji.wasm
+//! ```
+//!
+//! ```js
+//! import init, { parse, format, reading } from "./pkg/kansuji.js";
+//!
+//! await init();
+//! parse("一万二千三百四十五六分"); // "12345.6"
+//! format(35000, { daiji: true }); // "参萬伍阡"
+//! reading("三百"); // "さんびゃく"
+//! ```
+//!
+//! 漢数字として正しくない場合は、[`KansujiError`]のメッセージを持つ`Error`を投げる。
+
+use super::{Kansuji, KansujiError, KansujiFormat};
+use alloc::string::{String, ToString};
+use core::convert::TryFrom;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+#[wasm_bindgen(typescript_custom_section)]
+const FORMAT_OPTIONS: &str = r#"
+/** 漢数字への変換時の書式 */
+export interface FormatOptions {
+    /** 大字（壱・弐・参・拾・萬など）を使う */
+    daiji?: boolean;
+    /** 千の前の一を省略しない（一千） */
+    explicitOne?: boolean;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    /// 漢数字への変換時の書式（TypeScriptでは`FormatOptions`）
+    #[wasm_bindgen(typescript_type = "FormatOptions")]
+    pub type FormatOptions;
+
+    /// 漢数字にする値（TypeScriptでは`number | bigint | string`）
+    #[wasm_bindgen(typescript_type = "number | bigint | string")]
+    pub type Numeric;
+}
+
+fn to_js_error(e: KansujiError) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+/// 漢数字を読み、`"12345.6"`のような十進の文字列を返す
+///
+/// 大きな数も誤差なく扱えるよう、数値ではなく文字列で返す。
+#[wasm_bindgen]
+pub fn parse(s: &str) -> Result<String, JsError> {
+    parse_to_decimal(s).map_err(to_js_error)
+}
+
+/// 漢数字を読み、数値を返す
+///
+/// 2の53乗を超える値は誤差を含む。
+#[wasm_bindgen(js_name = parseNumber)]
+pub fn parse_number(s: &str) -> Result<f64, JsError> {
+    Kansuji::try_from(s).map(f64::from).map_err(to_js_error)
+}
+
+/// 数値・`BigInt`・十進の文字列を漢数字にする
+///
+/// 小数点以下は三桁までとし、それを超える場合は数値でも文字列でもエラーとする。
+#[wasm_bindgen]
+pub fn format(value: Numeric, options: Option<FormatOptions>) -> Result<String, JsError> {
+    let value: JsValue = value.into();
+    let kansuji = if let Some(n) = value.as_f64() {
+        decimal_from_f64(n)
+    } else if let Some(n) = value.dyn_ref::<js_sys::BigInt>() {
+        let s = n
+            .to_string(10)
+            .map_err(|_| JsError::new("invalid bigint"))?;
+        Kansuji::parse_decimal(&String::from(s))
+    } else if let Some(s) = value.as_string() {
+        Kansuji::parse_decimal(&s)
+    } else {
+        return Err(JsError::new("expected a number, bigint or string"));
+    }
+    .map_err(to_js_error)?;
+    let format = match options {
+        Some(options) => KansujiFormat {
+            daiji: option_flag(&options, "daiji"),
+            explicit_one: option_flag(&options, "explicitOne"),
+        },
+        None => KansujiFormat::default(),
+    };
+    Ok(kansuji.to_string_with(&format))
+}
+
+/// 数値を漢数字にする
+///
+/// 文字列と同じく扱うため、`1.2345`のように小数点以下が三桁を超える場合は切り捨てずにエラーとする。
+fn decimal_from_f64(n: f64) -> Result<Kansuji, KansujiError> {
+    Kansuji::try_from_f64(n)?;
+    Kansuji::parse_decimal(&n.to_string())
+}
+
+/// 書式の項目を読む（指定がない場合は`false`とする）
+fn option_flag(options: &FormatOptions, key: &str) -> bool {
+    js_sys::Reflect::get(options, &JsValue::from_str(key))
+        .ok()
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false)
+}
+
+/// 漢数字を読み、読みをひらがなで返す
+#[wasm_bindgen]
+pub fn reading(s: &str) -> Result<String, JsError> {
+    Kansuji::try_from(s)
+        .map(|kansuji| kansuji.reading())
+        .map_err(to_js_error)
+}
+
+fn parse_to_decimal(s: &str) -> Result<String, KansujiError> {
+    Kansuji::try_from(s).map(|kansuji| kansuji.to_decimal_string())
+}
+
+#[test]
+fn check_wasm_1() {
+    assert_eq!(
+        parse_to_decimal("一万二千三百四十五六分"),
+        Ok("12345.6".to_string())
+    );
+    assert_eq!(
+        parse_to_decimal("三百円"),
+        Err(KansujiError::UnexpectedChar('円'))
+    );
+}
@@ -0,0 +1,282 @@
+//! 円・銭・厘による金額の解析と変換を行う
+//!
+//! 1円は100銭、1銭は10厘であり、金額は厘を単位とした整数として保持する。
+//! 証書などで使われる「金…円也」の形式や大字、算用数字との混在にも対応する。
+//!
+//! ```
+//! use kansuji::Yen;
+//!
+//! let yen = Yen::try_from("金参萬伍阡円也").unwrap();
+//! assert_eq!(yen.to_rin(), 35000000);
+//! assert_eq!(yen.to_string(), "三万五千円");
+//! assert_eq!(yen.to_formal_string(), "金参萬伍阡円也");
+//!
+//! let yen = Yen::try_from("1,234円56銭").unwrap();
+//! assert_eq!(yen.to_decimal_string(), "1234.560");
+//!
+//! let yen = Yen::try_from("¥1,234").unwrap();
+//! assert_eq!(yen.to_string(), "千二百三十四円");
+//! ```
+
+use super::{arabic_digit, parse_integer, Kansuji, KansujiError, KansujiFormat, KANSUJI_LIMIT};
+use alloc::format;
+use alloc::string::String;
+#[cfg(test)]
+use alloc::string::ToString;
+use core::convert::{From, TryFrom};
+use core::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, PartialOrd, Ord, Hash)]
+pub struct Yen {
+    厘: u128,
+}
+
+impl Yen {
+    /// 厘を単位とした値から金額を作る
+    ///
+    /// 漢数字で表せない10の24乗円以上の金額はエラーとする。
+    pub fn from_rin(rin: u128) -> Result<Self, KansujiError> {
+        if rin / 1000 >= KANSUJI_LIMIT {
+            return Err(KansujiError::TooLarge);
+        }
+        Ok(Yen { 厘: rin })
+    }
+
+    /// 厘を単位とした値を返す
+    pub fn to_rin(&self) -> u128 {
+        self.厘
+    }
+
+    /// 円の部分を返す
+    pub fn yen(&self) -> u128 {
+        self.厘 / 1000
+    }
+
+    /// 銭の部分を返す
+    pub fn sen(&self) -> u8 {
+        ((self.厘 % 1000) / 10) as u8
+    }
+
+    /// 厘の部分を返す
+    pub fn rin(&self) -> u8 {
+        (self.厘 % 10) as u8
+    }
+
+    /// 円を単位とした小数表記の文字列に変換する
+    ///
+    /// 銭と厘があるため、小数点以下は常に3桁となる。
+    pub fn to_decimal_string(&self) -> String {
+        format!("{}.{:03}", self.yen(), self.厘 % 1000)
+    }
+
+    /// 書式を指定して漢数字に変換する
+    pub fn to_string_with(&self, format: &KansujiFormat) -> String {
+        let mut s = String::new();
+        if self.厘 == 0 {
+            s.push_str("零円");
+            return s;
+        }
+        if self.yen() != 0 {
+            s.push_str(&Kansuji::from(self.yen()).to_string_with(format));
+            s.push('円');
+        }
+        if self.sen() != 0 {
+            s.push_str(&Kansuji::from(self.sen()).to_string_with(format));
+            s.push('銭');
+        }
+        if self.rin() != 0 {
+            s.push_str(&Kansuji::from(self.rin()).to_string_with(format));
+            s.push('厘');
+        }
+        s
+    }
+
+    /// 大字を使い、「金…円也」の形式で漢数字に変換する
+    pub fn to_formal_string(&self) -> String {
+        let format = KansujiFormat {
+            daiji: true,
+            ..KansujiFormat::default()
+        };
+        format!("金{}也", self.to_string_with(&format))
+    }
+}
+
+impl TryFrom<String> for Yen {
+    type Error = KansujiError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_yen(&value)
+    }
+}
+
+impl TryFrom<&String> for Yen {
+    type Error = KansujiError;
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        parse_yen(value)
+    }
+}
+
+impl TryFrom<&str> for Yen {
+    type Error = KansujiError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_yen(value)
+    }
+}
+
+fn parse_yen(s: &str) -> Result<Yen, KansujiError> {
+    let s = s.strip_prefix('金').unwrap_or(s);
+    let (s, sign) = match s.strip_prefix(['¥', '￥']) {
+        Some(s) => (s, true),
+        None => (s, false),
+    };
+    let s = s.strip_suffix(['也', '整']).unwrap_or(s);
+    let mut rest = s;
+    let mut rin = None;
+    // ¥が付いている場合は円を省略できる（¥1,234）
+    let yen = match rest.split_once(['円', '圓']) {
+        Some((yen, r)) => Some((yen, r)),
+        None if sign => Some((rest, "")),
+        None => None,
+    };
+    if let Some((yen, r)) = yen {
+        let n = parse_amount(yen)?;
+        if n >= KANSUJI_LIMIT {
+            return Err(KansujiError::TooLarge);
+        }
+        rin = Some(n * 1000);
+        rest = r;
+    }
+    if let Some((sen, r)) = rest.split_once('銭') {
+        let n = parse_amount(sen)?;
+        if n >= 100 {
+            return Err(KansujiError::TooLarge);
+        }
+        rin = Some(rin.unwrap_or_default() + n * 10);
+        rest = r;
+    }
+    if let Some((r1, r)) = rest.split_once('厘') {
+        let n = parse_amount(r1)?;
+        if n >= 10 {
+            return Err(KansujiError::TooLarge);
+        }
+        rin = Some(rin.unwrap_or_default() + n);
+        rest = r;
+    }
+    let rin = rin.ok_or(KansujiError::UnexpectedEnd)?;
+    if let Some(c) = rest.chars().next() {
+        return Err(KansujiError::UnexpectedChar(c));
+    }
+    Yen::from_rin(rin)
+}
+
+/// 円・銭・厘の前に置かれた数を読む
+///
+/// 位取りのカンマは、後ろに算用数字がちょうど三桁続く場合に限って読み飛ばす。
+fn parse_amount(s: &str) -> Result<u128, KansujiError> {
+    for (i, c) in s.char_indices().filter(|(_, c)| matches!(c, ',' | '，')) {
+        let is_digit = |c: Option<char>| c.map_or(false, |c| arabic_digit(c).is_some());
+        let mut after = s[i + c.len_utf8()..].chars();
+        let grouped = is_digit(s[..i].chars().next_back())
+            && (0..3).all(|_| is_digit(after.next()))
+            && !is_digit(after.next());
+        if !grouped {
+            return Err(KansujiError::UnexpectedChar(c));
+        }
+    }
+    let s = s
+        .chars()
+        .filter(|c| !matches!(c, ',' | '，'))
+        .collect::<String>();
+    parse_integer(&s)
+}
+
+#[test]
+fn check_parse_yen_1() {
+    assert_eq!(parse_yen("金参萬伍阡円也"), Yen::from_rin(35000000));
+    assert_eq!(parse_yen("金三万五千円"), Yen::from_rin(35000000));
+    assert_eq!(parse_yen("三万五千円也"), Yen::from_rin(35000000));
+    assert_eq!(parse_yen("金壱百円整"), Yen::from_rin(100000));
+}
+
+#[test]
+fn check_parse_yen_2() {
+    assert_eq!(parse_yen("五円五十銭三厘"), Yen::from_rin(5503));
+    assert_eq!(parse_yen("五十銭"), Yen::from_rin(500));
+    assert_eq!(parse_yen("三厘"), Yen::from_rin(3));
+    assert_eq!(parse_yen("金拾圓也"), Yen::from_rin(10000));
+}
+
+#[test]
+fn check_parse_yen_3() {
+    assert_eq!(parse_yen("35,000円"), Yen::from_rin(35000000));
+    assert_eq!(parse_yen("￥１２３４円"), Yen::from_rin(1234000));
+    assert_eq!(parse_yen("3万5,000円"), Yen::from_rin(35000000));
+    assert_eq!(parse_yen("1234万円"), Yen::from_rin(12340000000));
+    assert_eq!(parse_yen("1円50銭"), Yen::from_rin(1500));
+    assert_eq!(parse_yen("¥1,234"), Yen::from_rin(1234000));
+    assert_eq!(parse_yen("1,234,567円"), Yen::from_rin(1234567000));
+}
+
+#[test]
+fn check_parse_yen_4() {
+    assert_eq!(parse_yen("三万"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_yen("円"), Err(KansujiError::UnexpectedEnd));
+    assert_eq!(parse_yen("百銭"), Err(KansujiError::TooLarge));
+    assert_eq!(parse_yen("十厘"), Err(KansujiError::TooLarge));
+    assert_eq!(
+        parse_yen("五円也です"),
+        Err(KansujiError::UnexpectedChar('也'))
+    );
+    assert_eq!(parse_yen("三分円"), Err(KansujiError::ParseError));
+    assert_eq!(parse_yen("1,2,3円"), Err(KansujiError::UnexpectedChar(',')));
+    assert_eq!(parse_yen("12,34円"), Err(KansujiError::UnexpectedChar(',')));
+    assert_eq!(parse_yen(",123円"), Err(KansujiError::UnexpectedChar(',')));
+    assert_eq!(
+        parse_yen("12345678901234567890123456円"),
+        Err(KansujiError::TooLarge)
+    );
+    assert_eq!(Yen::from_rin(u128::MAX), Err(KansujiError::TooLarge));
+    let max = Yen::from_rin(KANSUJI_LIMIT * 1000 - 1).unwrap();
+    assert_eq!(max.to_decimal_string(), "999999999999999999999999.999");
+    assert!(max.to_string().starts_with("九千九百九十九垓"));
+}
+
+impl fmt::Display for Yen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_string_with(&KansujiFormat::default()))
+    }
+}
+
+impl From<Yen> for f64 {
+    fn from(value: Yen) -> Self {
+        value.厘 as f64 / 1000.0
+    }
+}
+
+#[test]
+fn check_yen_1() {
+    fn yen_test_function(s: &str) {
+        let yen = Yen::try_from(s).unwrap();
+        assert_eq!(s, yen.to_string());
+    }
+
+    let v = [
+        "零円",
+        "一円",
+        "三万五千円",
+        "五円五十銭三厘",
+        "二十銭",
+        "一厘",
+    ];
+    v.iter().for_each(|s| yen_test_function(s));
+}
+
+#[test]
+fn check_yen_2() {
+    let yen = Yen::from_rin(1234560).unwrap();
+    assert_eq!(yen.yen(), 1234);
+    assert_eq!(yen.sen(), 56);
+    assert_eq!(yen.rin(), 0);
+    assert_eq!(yen.to_decimal_string(), "1234.560");
+    assert_eq!(f64::from(yen), 1234.56);
+    assert_eq!(yen.to_formal_string(), "金壱阡弐佰参拾肆円伍拾陸銭也");
+}
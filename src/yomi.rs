@@ -0,0 +1,312 @@
+//! 漢数字の読み（ひらがな）を生成する
+//!
+//! 三百（さんびゃく）や一兆（いっちょう）のような音便にも対応する。
+//!
+//! ```
+//! use kansuji::Kansuji;
+//!
+//! let kansuji = Kansuji::try_from("三百八十一兆").unwrap();
+//! assert_eq!(kansuji.reading(), "さんびゃくはちじゅういっちょう");
+//! ```
+
+use super::{Kansuji, KansujiField, KansujiKeta, Kurai};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// 数の後ろに続く語による音便の種類
+///
+/// 数の最後の読みが促音（っ）になるかどうかと、後ろに続く語の最初の音が濁るかどうかを決める。
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Onbin {
+    /// 音便がない（枚・台・人など）
+    None,
+    /// 一・六・八・十・百が促音になる（個・回など）
+    Ka,
+    /// `Ka`に加えて、三・千・万の後ろで濁音になる（階・軒など）
+    KaDakuon,
+    /// 一・八・十が促音になる（冊・歳・点など）
+    SaTa,
+    /// 一・六・八・十・百が促音になり、その後ろで半濁音、三・千・万の後ろで濁音になる（本・匹・杯など）
+    Ha,
+    /// 一・六・八・十・百が促音になり、その後ろと三・四・千・万の後ろで半濁音になる（分（ふん）など）
+    HaHandakuon,
+}
+
+/// 後ろに続く語の最初の音の変化
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Henka {
+    None,
+    Dakuon,
+    Handakuon,
+}
+
+impl KansujiField {
+    fn to_yomi(self) -> &'static str {
+        match self {
+            KansujiField::零 => "ぜろ",
+            KansujiField::一 => "いち",
+            KansujiField::二 => "に",
+            KansujiField::三 => "さん",
+            KansujiField::四 => "よん",
+            KansujiField::五 => "ご",
+            KansujiField::六 => "ろく",
+            KansujiField::七 => "なな",
+            KansujiField::八 => "はち",
+            KansujiField::九 => "きゅう",
+        }
+    }
+}
+
+impl KansujiKeta {
+    /// 読みを語ごとに分けて追加する
+    fn push_yomi(self, yomi: &mut Vec<&'static str>) {
+        match self.千 {
+            KansujiField::零 => (),
+            KansujiField::一 => yomi.push("せん"),
+            KansujiField::三 => yomi.extend(["さん", "ぜん"]),
+            KansujiField::八 => yomi.extend(["はっ", "せん"]),
+            f => yomi.extend([f.to_yomi(), "せん"]),
+        }
+        match self.百 {
+            KansujiField::零 => (),
+            KansujiField::一 => yomi.push("ひゃく"),
+            KansujiField::三 => yomi.extend(["さん", "びゃく"]),
+            KansujiField::六 => yomi.extend(["ろっ", "ぴゃく"]),
+            KansujiField::八 => yomi.extend(["はっ", "ぴゃく"]),
+            f => yomi.extend([f.to_yomi(), "ひゃく"]),
+        }
+        match self.十 {
+            KansujiField::零 => (),
+            KansujiField::一 => yomi.push("じゅう"),
+            f => yomi.extend([f.to_yomi(), "じゅう"]),
+        }
+        if self.一 != KansujiField::零 {
+            yomi.push(self.一.to_yomi());
+        }
+    }
+}
+
+/// 最後の語に音便を適用し、後ろに続く語の最初の音の変化を返す
+fn apply_onbin(yomi: &mut [&'static str], onbin: Onbin) -> Henka {
+    let last = match yomi.last_mut() {
+        Some(last) => last,
+        None => return Henka::None,
+    };
+    let sokuon = match (*last, onbin) {
+        (_, Onbin::None) => None,
+        ("いち", _) => Some("いっ"),
+        ("はち", _) => Some("はっ"),
+        ("じゅう", _) => Some("じゅっ"),
+        (_, Onbin::SaTa) => None,
+        ("ろく", _) => Some("ろっ"),
+        ("ひゃく", _) => Some("ひゃっ"),
+        ("びゃく", _) => Some("びゃっ"),
+        ("ぴゃく", _) => Some("ぴゃっ"),
+        _ => None,
+    };
+    if let Some(sokuon) = sokuon {
+        *last = sokuon;
+        return match onbin {
+            Onbin::Ha | Onbin::HaHandakuon => Henka::Handakuon,
+            _ => Henka::None,
+        };
+    }
+    match (*last, onbin) {
+        ("さん" | "せん" | "ぜん" | "まん", Onbin::KaDakuon | Onbin::Ha) => Henka::Dakuon,
+        ("さん" | "よん" | "せん" | "ぜん" | "まん", Onbin::HaHandakuon) => {
+            Henka::Handakuon
+        }
+        _ => Henka::None,
+    }
+}
+
+/// 語の最初の音を濁音または半濁音にする
+fn apply_henka(s: &str, henka: Henka) -> String {
+    let mut chars = s.chars();
+    let first = match chars.next() {
+        Some(c) => c,
+        None => return String::new(),
+    };
+    let first = match henka {
+        Henka::None => first,
+        Henka::Dakuon => match first {
+            'か' => 'が',
+            'き' => 'ぎ',
+            'く' => 'ぐ',
+            'け' => 'げ',
+            'こ' => 'ご',
+            'さ' => 'ざ',
+            'し' => 'じ',
+            'す' => 'ず',
+            'せ' => 'ぜ',
+            'そ' => 'ぞ',
+            'た' => 'だ',
+            'ち' => 'ぢ',
+            'つ' => 'づ',
+            'て' => 'で',
+            'と' => 'ど',
+            'は' => 'ば',
+            'ひ' => 'び',
+            'ふ' => 'ぶ',
+            'へ' => 'べ',
+            'ほ' => 'ぼ',
+            c => c,
+        },
+        Henka::Handakuon => match first {
+            'は' => 'ぱ',
+            'ひ' => 'ぴ',
+            'ふ' => 'ぷ',
+            'へ' => 'ぺ',
+            'ほ' => 'ぽ',
+            c => c,
+        },
+    };
+    let mut s = String::new();
+    s.push(first);
+    s.push_str(chars.as_str());
+    s
+}
+
+impl Kansuji {
+    /// 整数部の読みを語ごとに分けて返す
+    fn integer_yomi(&self) -> Vec<&'static str> {
+        let mut yomi = Vec::new();
+        for (keta, unit, onbin) in [
+            (self.keta(Kurai::垓), "がい", Onbin::None),
+            (self.keta(Kurai::京), "けい", Onbin::Ka),
+            (self.keta(Kurai::兆), "ちょう", Onbin::SaTa),
+            (self.keta(Kurai::億), "おく", Onbin::None),
+            (self.keta(Kurai::万), "まん", Onbin::None),
+        ] {
+            if !keta.is_zero() {
+                keta.push_yomi(&mut yomi);
+                apply_onbin(&mut yomi, onbin);
+                yomi.push(unit);
+            }
+        }
+        self.keta(Kurai::一).push_yomi(&mut yomi);
+        yomi
+    }
+
+    /// 読みをひらがなで返す
+    pub fn reading(&self) -> String {
+        let mut yomi = self.integer_yomi();
+        if yomi.is_empty() && !self.has_fraction() {
+            return KansujiField::零.to_yomi().to_string();
+        }
+        for (kurai, unit) in [(Kurai::分, "ぶ"), (Kurai::厘, "りん"), (Kurai::毛, "もう")] {
+            let field = self.field(kurai);
+            if field != KansujiField::零 {
+                yomi.push(field.to_yomi());
+                yomi.push(unit);
+            }
+        }
+        yomi.concat()
+    }
+
+    /// 後ろに語が続く場合の読みをひらがなで返す
+    ///
+    /// `ones`は一の位の数字の読みを置き換えるもの（四人（よにん）など）である。
+    pub(crate) fn reading_with(&self, word: &str, onbin: Onbin, ones: &[(u8, String)]) -> String {
+        if self.has_fraction() {
+            return format!("{}{}", self.reading(), word);
+        }
+        let mut yomi = self.integer_yomi();
+        if yomi.is_empty() {
+            return format!("{}{}", KansujiField::零.to_yomi(), word);
+        }
+        let mut s = String::new();
+        let ichi = self.keta(Kurai::一).一;
+        if let Some((_, r)) = ones
+            .iter()
+            .find(|(d, _)| ichi != KansujiField::零 && *d == ichi.to_int())
+        {
+            yomi.pop();
+            s.push_str(&yomi.concat());
+            s.push_str(r);
+            s.push_str(word);
+            return s;
+        }
+        let henka = apply_onbin(&mut yomi, onbin);
+        s.push_str(&yomi.concat());
+        s.push_str(&apply_henka(word, henka));
+        s
+    }
+}
+
+#[test]
+fn check_reading_1() {
+    fn reading_test_function((n, s): &(u128, &str)) {
+        assert_eq!(Kansuji::from(*n).reading(), *s);
+    }
+
+    let v = [
+        (0, "ぜろ"),
+        (1, "いち"),
+        (10, "じゅう"),
+        (14, "じゅうよん"),
+        (300, "さんびゃく"),
+        (600, "ろっぴゃく"),
+        (800, "はっぴゃく"),
+        (3000, "さんぜん"),
+        (8000, "はっせん"),
+        (10000, "いちまん"),
+        (11000000, "せんひゃくまん"),
+        (100000000, "いちおく"),
+        (1000000000000, "いっちょう"),
+        (8000000000000, "はっちょう"),
+        (10000000000000, "じゅっちょう"),
+        (6_0000_0000_0000_0000, "ろっけい"),
+        (100_0000_0000_0000_0000, "ひゃっけい"),
+        (1_0000_0000_0000_0000_0000, "いちがい"),
+    ];
+    v.iter().for_each(reading_test_function);
+}
+
+#[test]
+fn check_reading_2() {
+    let kansuji = Kansuji::from(1.25);
+    assert_eq!(kansuji.reading(), "いちにぶごりん");
+    let kansuji = Kansuji::from(0.5);
+    assert_eq!(kansuji.reading(), "ごぶ");
+}
+
+#[test]
+fn check_reading_with_1() {
+    fn hon(n: u128) -> String {
+        Kansuji::from(n).reading_with("ほん", Onbin::Ha, &[])
+    }
+    assert_eq!(hon(1), "いっぽん");
+    assert_eq!(hon(2), "にほん");
+    assert_eq!(hon(3), "さんぼん");
+    assert_eq!(hon(4), "よんほん");
+    assert_eq!(hon(6), "ろっぽん");
+    assert_eq!(hon(10), "じゅっぽん");
+    assert_eq!(hon(300), "さんびゃっぽん");
+    assert_eq!(hon(1000), "せんぼん");
+    assert_eq!(hon(10000), "いちまんぼん");
+}
+
+#[test]
+fn check_reading_with_2() {
+    let kansuji = Kansuji::from(3_u8);
+    assert_eq!(
+        kansuji.reading_with("ふん", Onbin::HaHandakuon, &[]),
+        "さんぷん"
+    );
+    assert_eq!(
+        kansuji.reading_with("かい", Onbin::KaDakuon, &[]),
+        "さんがい"
+    );
+    assert_eq!(kansuji.reading_with("かい", Onbin::Ka, &[]), "さんかい");
+    let kansuji = Kansuji::from(6_u8);
+    assert_eq!(kansuji.reading_with("さい", Onbin::SaTa, &[]), "ろくさい");
+    assert_eq!(kansuji.reading_with("こ", Onbin::Ka, &[]), "ろっこ");
+    let kansuji = Kansuji::from(14_u8);
+    let ones = [(4, "よ".to_string())];
+    assert_eq!(
+        kansuji.reading_with("にん", Onbin::None, &ones),
+        "じゅうよにん"
+    );
+}
@@ -0,0 +1,46 @@
+//! `include/kansuji.h`を使うCのプログラムを、静的ライブラリとしてビルドしたkansujiとリンクして実行する
+
+#![cfg(all(feature = "ffi", unix))]
+
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn check_c_program_1() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi");
+
+    // テスト自体のビルドと競合しないよう、別のディレクトリに静的ライブラリを作る
+    let status = Command::new(env!("CARGO"))
+        .current_dir(manifest_dir)
+        .args(["rustc", "--lib", "--crate-type", "staticlib"])
+        .args(["--no-default-features", "--features", "ffi"])
+        .arg("--target-dir")
+        .arg(out_dir.join("target"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let program = out_dir.join("kansuji_test");
+    let status = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests/ffi/kansuji_test.c"))
+        .arg(out_dir.join("target/debug/libkansuji.a"))
+        .args(["-lpthread", "-ldl", "-lm"])
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(output.stdout, b"ok\n");
+}
@@ -0,0 +1,62 @@
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "kansuji.h"
+
+static void check_parse(void) {
+  const char *s = "一万二千三百四十五六分";
+  KansujiValue value;
+  assert(kansuji_parse(s, strlen(s), &value) == KANSUJI_STATUS_OK);
+  assert(value.integer_high == 0);
+  assert(value.integer_low == 12345);
+  assert(value.fraction == 600);
+
+  s = "三百円";
+  assert(kansuji_parse(s, strlen(s), &value) == KANSUJI_STATUS_UNEXPECTED_CHAR);
+  assert(strcmp(kansuji_last_error_message(), "unexpected char: 円") == 0);
+
+  assert(kansuji_parse(s, strlen(s), NULL) == KANSUJI_STATUS_NULL_POINTER);
+}
+
+static void check_parse_to_decimal(void) {
+  const char *s = "九千九百九十九垓";
+  char buf[KANSUJI_BUFFER_CAPACITY];
+  size_t written;
+  assert(kansuji_parse_to_decimal(s, strlen(s), buf, sizeof(buf), &written) ==
+         KANSUJI_STATUS_OK);
+  assert(strcmp(buf, "999900000000000000000000") == 0);
+  assert(written == strlen(buf));
+}
+
+static void check_format(void) {
+  char buf[KANSUJI_BUFFER_CAPACITY];
+  size_t written;
+  KansujiValue value = {0, 35000, 0};
+  KansujiFormatOptions options = {true, false};
+  assert(kansuji_format(value, &options, buf, sizeof(buf), &written) ==
+         KANSUJI_STATUS_OK);
+  assert(strcmp(buf, "参萬伍阡") == 0);
+
+  assert(kansuji_format(value, NULL, buf, 4, &written) ==
+         KANSUJI_STATUS_BUFFER_TOO_SMALL);
+  assert(written == strlen("三万五千"));
+
+  const char *s = "1.5";
+  assert(kansuji_format_decimal(s, strlen(s), NULL, buf, sizeof(buf), NULL) ==
+         KANSUJI_STATUS_OK);
+  assert(strcmp(buf, "一五分") == 0);
+
+  s = "1.2345";
+  assert(kansuji_format_decimal(s, strlen(s), NULL, buf, sizeof(buf), NULL) ==
+         KANSUJI_STATUS_PARSE_ERROR);
+  assert(strcmp(kansuji_last_error_message(), "parse error") == 0);
+}
+
+int main(void) {
+  check_parse();
+  check_parse_to_decimal();
+  check_format();
+  printf("ok\n");
+  return 0;
+}
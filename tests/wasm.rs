@@ -0,0 +1,68 @@
+//! `wasm-bindgen-test`でNode.js上で実行する
+//!
+//! ```sh
+//! cargo test --target wasm32-unknown-unknown --features wasm --test wasm
+//! ```
+
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use kansuji::wasm::{format, parse, parse_number, reading, FormatOptions, Numeric};
+use wasm_bindgen::{JsCast, JsError, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn message(e: JsError) -> String {
+    JsValue::from(e)
+        .unchecked_into::<js_sys::Error>()
+        .message()
+        .into()
+}
+
+fn numeric<T: Into<JsValue>>(value: T) -> Numeric {
+    value.into().unchecked_into()
+}
+
+#[wasm_bindgen_test]
+fn check_parse_1() {
+    assert_eq!(parse("一万二千三百四十五六分").unwrap(), "12345.6");
+    assert_eq!(
+        parse("九千九百九十九垓").unwrap(),
+        "999900000000000000000000"
+    );
+    assert_eq!(parse_number("三百二十").unwrap(), 320.0);
+    assert_eq!(message(parse("三百円").unwrap_err()), "unexpected char: 円");
+}
+
+#[wasm_bindgen_test]
+fn check_format_1() {
+    assert_eq!(format(numeric(35000), None).unwrap(), "三万五千");
+    assert_eq!(format(numeric(1.5), None).unwrap(), "一五分");
+    assert_eq!(format(numeric("1000"), None).unwrap(), "千");
+    assert_eq!(
+        format(numeric(js_sys::BigInt::from(1_0000_0000_0000_u64)), None).unwrap(),
+        "一兆"
+    );
+
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &"daiji".into(), &true.into()).unwrap();
+    let options: FormatOptions = options.unchecked_into();
+    assert_eq!(format(numeric(35000), Some(options)).unwrap(), "参萬伍阡");
+
+    assert_eq!(
+        message(format(numeric(-1), None).unwrap_err()),
+        "out of range"
+    );
+    assert_eq!(
+        message(format(numeric(1.2345), None).unwrap_err()),
+        message(format(numeric("1.2345"), None).unwrap_err())
+    );
+    assert_eq!(
+        message(format(numeric(true), None).unwrap_err()),
+        "expected a number, bigint or string"
+    );
+}
+
+#[wasm_bindgen_test]
+fn check_reading_1() {
+    assert_eq!(reading("三百").unwrap(), "さんびゃく");
+    assert!(reading("三百円").is_err());
+}